@@ -1,13 +1,23 @@
 use std::cell::RefCell;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use globset::{Glob, GlobMatcher};
-use rand::RngExt;
-use std::collections::VecDeque;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
 use unicode_width::UnicodeWidthStr;
 
-use crate::git::{CommitMetadata, DiffHunk, FileChange, FileStatus, LineChangeType};
+use crate::clock::{Clock, SystemClock};
+use crate::completion::{Chunk, Completer};
+use crate::git::{CommitMetadata, DiffHunk, FileChange, FileStatus, LineChangeType, LineEnding};
+use crate::preview::BinaryChange;
 use crate::syntax::Highlighter;
+use crate::typing::{Key, TypingModel, TypingSettings};
 
 /// A rule that specifies typing speed for files matching a glob pattern
 #[derive(Debug, Clone)]
@@ -37,6 +47,172 @@ impl SpeedRule {
     }
 }
 
+/// How long each commit should take to play: a target, or bounds that
+/// commits are sped up or slowed down to fit within
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommitDuration {
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+impl CommitDuration {
+    /// Factor for the delays of a commit that would take `estimate`, so
+    /// that it takes a duration within the bounds.
+    pub fn scale(&self, estimate: Duration) -> f64 {
+        if estimate.is_zero() {
+            return 1.0;
+        }
+        let mut target = estimate;
+        if let Some(min) = self.min {
+            target = target.max(min);
+        }
+        if let Some(max) = self.max {
+            target = target.min(max);
+        }
+        target.as_secs_f64() / estimate.as_secs_f64()
+    }
+}
+
+impl FromStr for CommitDuration {
+    type Err = String;
+
+    /// Parses "45s" as a target, or "30s..2m", "20s.." and "..1m" as bounds.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bound = |part: &str| -> Result<Option<Duration>, String> {
+            let part = part.trim();
+            if part.is_empty() {
+                Ok(None)
+            } else {
+                parse_duration(part).map(Some).ok_or_else(|| {
+                    format!("invalid duration '{}', expected e.g. 45s or 1m30s", part)
+                })
+            }
+        };
+
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => (bound(min)?, bound(max)?),
+            None => {
+                let target = bound(s)?.ok_or("missing duration")?;
+                (Some(target), Some(target))
+            }
+        };
+        if min.is_none() && max.is_none() {
+            return Err("expected at least one bound".to_string());
+        }
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err("minimum exceeds maximum".to_string());
+            }
+        }
+        Ok(Self { min, max })
+    }
+}
+
+// Parses durations such as "90", "45s", "2m", "1m30s", "1h" or "500ms";
+// bare numbers are seconds
+fn parse_duration(s: &str) -> Option<Duration> {
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let part = match &rest[..unit] {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => Duration::from_secs(value.checked_mul(60)?),
+            "h" => Duration::from_secs(value.checked_mul(3600)?),
+            _ => return None,
+        };
+        total = total.checked_add(part)?;
+        rest = &rest[unit..];
+    }
+    Some(total)
+}
+
+/// Order in which the hunks of a commit are edited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HunkOrder {
+    /// Each file from its first hunk to its last
+    #[default]
+    TopDown,
+    /// Each file from its last hunk to its first
+    BottomUp,
+    /// Declarations and imports first, then the remaining hunks
+    SignatureFirst,
+    /// Hunks of each file in random order
+    Random,
+    /// Alternate between hunks of related files in the same directory or
+    /// with the same name
+    Interleave,
+}
+
+/// Named playback rates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SpeedPreset {
+    /// Half speed, for following along closely
+    Relaxed,
+    /// Typing speed as configured
+    #[default]
+    Normal,
+    /// Twice as fast, for presentations
+    Demo,
+    /// Eight times as fast, to skim through history
+    Turbo,
+}
+
+impl SpeedPreset {
+    /// How many times faster than the configured typing speed steps play.
+    pub fn rate(self) -> f64 {
+        match self {
+            Self::Relaxed => 0.5,
+            Self::Normal => 1.0,
+            Self::Demo => 2.0,
+            Self::Turbo => 8.0,
+        }
+    }
+}
+
+/// First words of lines that declare or import something
+const SIGNATURE_KEYWORDS: &[&str] = &[
+    "use",
+    "import",
+    "from",
+    "#include",
+    "#import",
+    "require",
+    "package",
+    "mod",
+    "module",
+    "namespace",
+    "using",
+    "extern",
+    "pub",
+    "export",
+    "fn",
+    "def",
+    "func",
+    "function",
+    "class",
+    "struct",
+    "enum",
+    "trait",
+    "interface",
+    "impl",
+    "type",
+    "const",
+    "static",
+];
+/// Largest group of files whose hunks are interleaved
+const MAX_INTERLEAVED_FILES: usize = 3;
+
 // Duration multipliers relative to typing speed
 const CURSOR_MOVE_PAUSE: f64 = 0.5; // Cursor movement between lines (base speed)
 const CURSOR_MOVE_SHORT_MULTIPLIER: f64 = 1.0; // Speed for short distances (1-50 lines)
@@ -61,14 +237,24 @@ const COMMIT_OUTPUT_PAUSE: f64 = 33.3; // After commit output
 const GIT_PUSH_PAUSE: f64 = 16.7; // After git push command
 const PUSH_OUTPUT_PAUSE: f64 = 10.0; // Between push output lines
 const PUSH_FINAL_PAUSE: f64 = 66.7; // After final push output
-
-const MAX_LINE_CHECKPOINTS: usize = 200;
-const MAX_CHANGE_CHECKPOINTS: usize = 64;
+const DEPENDENCY_LINE_PAUSE: f64 = 3.0; // Between lock file summary lines
+const MAX_DEPENDENCY_LINES: usize = 20;
+const BINARY_PREVIEW_PAUSE: f64 = 100.0; // While showing a binary or image preview
+const COMPLETION_PAUSE: f64 = 8.0; // While the completion popup is open
+
+/// Steps between the snapshots that seeking replays from, at first
+const SEEK_SNAPSHOT_INTERVAL: usize = 500;
+/// Snapshots kept per commit; beyond this the interval doubles
+const MAX_SEEK_SNAPSHOTS: usize = 32;
+/// Extra playback rate while fast-forwarding through a file
+const FAST_FORWARD_RATE: f64 = 10.0;
 
 /// Represents the current state of the editor buffer
 #[derive(Debug, Clone)]
 pub struct EditorBuffer {
     pub lines: Vec<String>,
+    /// Terminator of each line, parallel to `lines`
+    pub line_endings: Vec<LineEnding>,
     pub cursor_line: usize,
     pub cursor_col: usize,
     pub scroll_offset: usize,
@@ -84,11 +270,18 @@ pub struct EditorBuffer {
     pub new_content_line_offsets: Vec<usize>,
 }
 
+impl Default for EditorBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EditorBuffer {
     /// Creates a new empty editor buffer with default values.
     pub fn new() -> Self {
         Self {
             lines: vec![String::new()],
+            line_endings: vec![LineEnding::NoNewline],
             cursor_line: 0,
             cursor_col: 0,
             scroll_offset: 0,
@@ -104,14 +297,21 @@ impl EditorBuffer {
 
     /// Creates an editor buffer initialized with the given content.
     pub fn from_content(content: &str) -> Self {
-        let lines: Vec<String> = if content.is_empty() {
-            vec![String::new()]
+        let (lines, line_endings) = if content.is_empty() {
+            (vec![String::new()], vec![LineEnding::NoNewline])
         } else {
-            content.lines().map(|s| s.to_string()).collect()
+            content
+                .split_inclusive('\n')
+                .map(|line| {
+                    let (text, ending) = LineEnding::split(line);
+                    (text.to_string(), ending)
+                })
+                .unzip()
         };
 
         Self {
             lines,
+            line_endings,
             cursor_line: 0,
             cursor_col: 0,
             scroll_offset: 0,
@@ -129,6 +329,7 @@ impl EditorBuffer {
     pub fn insert_char(&mut self, line: usize, col: usize, ch: char) {
         if line >= self.lines.len() {
             self.lines.resize(line + 1, String::new());
+            self.line_endings.resize(line + 1, LineEnding::Lf);
         }
         let line_str = &mut self.lines[line];
 
@@ -142,36 +343,69 @@ impl EditorBuffer {
         line_str.insert(byte_idx, ch);
     }
 
+    /// Removes the character at the specified position, if any.
+    pub fn delete_char(&mut self, line: usize, col: usize) {
+        let Some(line_str) = self.lines.get_mut(line) else {
+            return;
+        };
+        if let Some((byte_idx, _)) = line_str.char_indices().nth(col) {
+            line_str.remove(byte_idx);
+        }
+    }
+
     /// Inserts a new line with the given content at the specified position.
-    pub fn insert_line(&mut self, line: usize, content: String) {
+    pub fn insert_line(&mut self, line: usize, content: String, line_ending: LineEnding) {
         if line > self.lines.len() {
             self.lines.resize(line, String::new());
+            self.line_endings.resize(line, LineEnding::Lf);
         }
         self.lines.insert(line, content);
+        self.line_endings.insert(line, line_ending);
     }
 
     /// Deletes the line at the specified position.
     pub fn delete_line(&mut self, line: usize) {
         if line < self.lines.len() {
             self.lines.remove(line);
+            self.line_endings.remove(line);
         }
         if self.lines.is_empty() {
             self.lines.push(String::new());
+            self.line_endings.push(LineEnding::NoNewline);
         }
     }
+
+    /// Returns the buffer as file content, with the original line endings.
+    pub fn content(&self) -> String {
+        self.lines
+            .iter()
+            .zip(&self.line_endings)
+            .map(|(line, ending)| format!("{}{}", line, ending.as_str()))
+            .collect()
+    }
 }
 
 /// Individual animation step
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
 pub enum AnimationStep {
     InsertChar {
         line: usize,
         col: usize,
         ch: char,
+        /// Delay before the next step, relative to the typing speed
+        delay: f64,
+    },
+    /// Backspace over the character at `col`
+    DeleteChar {
+        line: usize,
+        col: usize,
+        delay: f64,
     },
     InsertLine {
         line: usize,
         content: String,
+        line_ending: LineEnding,
     },
     DeleteLine {
         line: usize,
@@ -188,19 +422,54 @@ pub enum AnimationStep {
         old_content: String,
         new_content: String,
         path: String,
+        /// Path used for syntax highlighting when it differs from `path`
+        syntax_path: Option<String>,
     },
     OpenFileDialogStart,
     DialogTypeChar {
         ch: char,
+        delay: f64,
     },
     TerminalPrompt,
     TerminalTypeChar {
         ch: char,
+        delay: f64,
+    },
+    TerminalBackspace {
+        delay: f64,
     },
     TerminalOutput {
         text: String,
     },
+    /// Open the completion popup for the identifier starting at `col`
+    ShowCompletion {
+        line: usize,
+        col: usize,
+        candidates: Vec<String>,
+        selected: usize,
+    },
+    /// Accept the selected completion, inserting the rest of the identifier
+    AcceptCompletion {
+        line: usize,
+        col: usize,
+        text: String,
+    },
     ResetState,
+    /// Show a commit replayed from a bumped submodule, or the parent commit
+    /// again when `index` is None
+    ShowSubmoduleCommit {
+        index: Option<usize>,
+    },
+}
+
+/// Completion popup shown while an identifier is typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionPopup {
+    pub line: usize,
+    /// Column where the completed identifier starts
+    pub col: usize,
+    pub candidates: Vec<String>,
+    pub selected: usize,
 }
 
 /// Animation state machine
@@ -224,8 +493,38 @@ pub enum StepMode {
     Change,
 }
 
+/// Editing progress through the hunks of one file
+struct FileProgress<'a> {
+    change: &'a FileChange,
+    /// Hunks left to edit, in edit order
+    pending: VecDeque<usize>,
+    /// Hunks already applied to the buffer
+    applied: Vec<usize>,
+    /// Old start and line count change of each applied hunk
+    line_deltas: Vec<(usize, i64)>,
+    /// Lines of the old content, for indentation during cursor movement
+    old_lines: Vec<&'a str>,
+    cursor_line: usize,
+    completer: Option<Completer>,
+}
+
+/// Position in the edits of a commit that stays the same between runs,
+/// unlike step indexes, which typos and random hunk order change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditAnchor {
+    /// Index of the open file in the commit's changes
+    pub file: usize,
+    /// Hunk being edited and line within it, counted from 0; unset until
+    /// the first edit after the file is opened
+    pub hunk: Option<usize>,
+    pub line: Option<usize>,
+    /// Steps played since the file was opened or the line was started
+    pub offset: usize,
+}
+
+/// State of the engine before a step, which seeking replays from
 #[derive(Clone)]
-struct ManualCheckpoint {
+struct Snapshot {
     step_index: usize,
     buffer: EditorBuffer,
     current_file_index: usize,
@@ -235,17 +534,17 @@ struct ManualCheckpoint {
     line_offset: isize,
     dialog_title: Option<String>,
     dialog_typing_text: String,
+    completion: Option<CompletionPopup>,
     speed_ms: u64,
+    submodule_commit: Option<usize>,
+    current_metadata: Option<Arc<CommitMetadata>>,
+    pending_metadata: Option<Arc<CommitMetadata>>,
 }
 
-impl ManualCheckpoint {
+impl Snapshot {
     fn new(engine: &AnimationEngine) -> Self {
-        let resume_step = engine
-            .current_step
-            .saturating_add(1)
-            .min(engine.steps.len());
         Self {
-            step_index: resume_step,
+            step_index: engine.current_step,
             buffer: engine.buffer.clone(),
             current_file_index: engine.current_file_index,
             current_file_path: engine.current_file_path.clone(),
@@ -254,7 +553,11 @@ impl ManualCheckpoint {
             line_offset: engine.line_offset,
             dialog_title: engine.dialog_title.clone(),
             dialog_typing_text: engine.dialog_typing_text.clone(),
+            completion: engine.completion.clone(),
             speed_ms: engine.speed_ms,
+            submodule_commit: engine.submodule_commit,
+            current_metadata: engine.current_metadata.clone(),
+            pending_metadata: engine.pending_metadata.clone(),
         }
     }
 }
@@ -298,15 +601,47 @@ pub struct AnimationEngine {
     pub dialog_title: Option<String>,
     /// Text being typed in the dialog
     pub dialog_typing_text: String,
+    /// Completion popup in the editor, if open
+    pub completion: Option<CompletionPopup>,
+    /// Complete identifiers already in the buffer instead of typing them out
+    autocomplete: bool,
     /// Current metadata being displayed
-    current_metadata: Option<CommitMetadata>,
+    current_metadata: Option<Arc<CommitMetadata>>,
     /// Pending metadata to be applied on ResetState
-    pending_metadata: Option<CommitMetadata>,
+    pending_metadata: Option<Arc<CommitMetadata>>,
+    /// Submodule commits replayed inside the loaded commit
+    submodule_commits: Vec<CommitMetadata>,
+    /// Index of the submodule commit being replayed, if any
+    submodule_commit: Option<usize>,
     /// Speed rules for different file patterns
     speed_rules: Vec<SpeedRule>,
+    /// Decides the keystrokes and rhythm of typed text
+    typing: Box<dyn TypingModel>,
+    /// Source of every random choice made while generating steps
+    rng: StdRng,
+    /// Source of the current time, real or simulated
+    clock: Box<dyn Clock>,
+    hunk_order: HunkOrder,
     paused: bool,
-    line_checkpoints: VecDeque<ManualCheckpoint>,
-    change_checkpoints: VecDeque<ManualCheckpoint>,
+    /// Step indices that stepping back by line or change returns to
+    line_checkpoints: BTreeSet<usize>,
+    change_checkpoints: BTreeSet<usize>,
+    /// State every `seek_interval` steps of the loaded commit
+    seek_snapshots: Vec<Snapshot>,
+    seek_interval: usize,
+    /// Speed-up applied to every delay, kept across commits and seeks
+    playback_rate: f64,
+    /// Whether the rest of the open file plays at `FAST_FORWARD_RATE`
+    fast_forward: bool,
+    /// Duration that each commit is fitted to, if any
+    commit_duration: Option<CommitDuration>,
+    /// Factor applied to every delay to fit the loaded commit
+    commit_scale: f64,
+    /// Steps that playback repeats until cleared
+    loop_range: Option<Range<usize>>,
+    /// Step at which each line of a hunk starts to be edited, with the
+    /// hunk and line indexes, in step order
+    line_starts: Vec<(usize, usize, usize)>,
 }
 
 impl AnimationEngine {
@@ -314,7 +649,8 @@ impl AnimationEngine {
     pub fn new(speed_ms: u64) -> Self {
         let target_fps: u64 = 120;
         let frame_interval_ms = 1000 / target_fps;
-        let now = Instant::now();
+        let clock: Box<dyn Clock> = Box::new(SystemClock);
+        let now = clock.now();
         Self {
             buffer: EditorBuffer::new(),
             state: AnimationState::Idle,
@@ -340,12 +676,28 @@ impl AnimationEngine {
             last_frame: now,
             dialog_title: None,
             dialog_typing_text: String::new(),
+            completion: None,
+            autocomplete: true,
             current_metadata: None,
             pending_metadata: None,
+            submodule_commits: Vec::new(),
+            submodule_commit: None,
             speed_rules: Vec::new(),
+            typing: TypingSettings::default().build(rand::random()),
+            rng: StdRng::from_rng(&mut rand::rng()),
+            clock,
+            hunk_order: HunkOrder::default(),
             paused: false,
-            line_checkpoints: VecDeque::new(),
-            change_checkpoints: VecDeque::new(),
+            line_checkpoints: BTreeSet::new(),
+            change_checkpoints: BTreeSet::new(),
+            seek_snapshots: Vec::new(),
+            seek_interval: SEEK_SNAPSHOT_INTERVAL,
+            playback_rate: 1.0,
+            fast_forward: false,
+            commit_duration: None,
+            commit_scale: 1.0,
+            loop_range: None,
+            line_starts: Vec::new(),
         }
     }
 
@@ -358,7 +710,7 @@ impl AnimationEngine {
     pub fn resume(&mut self) {
         if self.paused {
             self.paused = false;
-            let now = Instant::now();
+            let now = self.clock.now();
             self.last_update = now;
             self.last_frame = now;
         }
@@ -394,7 +746,7 @@ impl AnimationEngine {
         }
 
         if executed {
-            let now = Instant::now();
+            let now = self.clock.now();
             self.last_update = now;
             self.last_frame = now;
         }
@@ -402,33 +754,51 @@ impl AnimationEngine {
         executed
     }
 
-    pub fn restore_line_checkpoint(&mut self) -> bool {
-        if self.line_checkpoints.len() < 2 {
-            return false;
-        }
-        self.line_checkpoints.pop_back();
-        if let Some(snapshot) = self.line_checkpoints.back().cloned() {
-            self.apply_checkpoint(snapshot);
-            true
-        } else {
-            false
+    /// Returns the step that will be executed next.
+    pub fn peek_step(&self) -> Option<&AnimationStep> {
+        self.steps.get(self.current_step)
+    }
+
+    /// Execute the next step immediately, ignoring its timing.
+    /// Returns the executed step, or `None` once all steps have run.
+    pub fn advance(&mut self) -> Option<AnimationStep> {
+        let step = self.steps.get(self.current_step)?.clone();
+        self.execute_step(step.clone());
+        self.current_step += 1;
+
+        if self.current_step >= self.steps.len() {
+            self.state = AnimationState::Finished;
         }
+
+        Some(step)
+    }
+
+    pub fn restore_line_checkpoint(&mut self) -> bool {
+        self.restore_checkpoint(CheckpointKind::Line)
     }
 
     pub fn restore_change_checkpoint(&mut self) -> bool {
-        if self.change_checkpoints.len() < 2 {
-            return false;
-        }
-        self.change_checkpoints.pop_back();
-        if let Some(snapshot) = self.change_checkpoints.back().cloned() {
-            self.apply_checkpoint(snapshot);
-            true
-        } else {
-            false
+        self.restore_checkpoint(CheckpointKind::Change)
+    }
+
+    // Goes back to the checkpoint before the one last passed, so that
+    // stepping back from the middle of a line starts the previous line
+    fn restore_checkpoint(&mut self, kind: CheckpointKind) -> bool {
+        let checkpoints = match kind {
+            CheckpointKind::Line => &self.line_checkpoints,
+            CheckpointKind::Change => &self.change_checkpoints,
+        };
+        match checkpoints.range(..=self.current_step).rev().nth(1) {
+            Some(&target) => {
+                self.seek(target);
+                self.paused = true;
+                true
+            }
+            None => false,
         }
     }
 
-    fn apply_checkpoint(&mut self, snapshot: ManualCheckpoint) {
+    fn restore(&mut self, snapshot: Snapshot) {
         self.current_step = snapshot.step_index;
         self.buffer = snapshot.buffer;
         self.current_file_index = snapshot.current_file_index;
@@ -438,12 +808,191 @@ impl AnimationEngine {
         self.line_offset = snapshot.line_offset;
         self.dialog_title = snapshot.dialog_title;
         self.dialog_typing_text = snapshot.dialog_typing_text;
+        self.completion = snapshot.completion;
         self.speed_ms = snapshot.speed_ms;
+        self.submodule_commit = snapshot.submodule_commit;
+        self.current_metadata = snapshot.current_metadata;
+        self.pending_metadata = snapshot.pending_metadata;
         self.pause_until = None;
-        self.paused = true;
         self.state = AnimationState::Playing;
     }
 
+    /// Returns the number of steps executed and the number of steps of the
+    /// loaded commit.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.current_step, self.steps.len())
+    }
+
+    /// Rebuilds the state after the first `step` steps of the loaded commit,
+    /// replaying steps without their timing from the nearest snapshot.
+    /// Playback continues from there unless paused.
+    pub fn seek(&mut self, step: usize) {
+        let target = step.min(self.steps.len());
+        let snapshot = self
+            .seek_snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.step_index <= target)
+            .filter(|snapshot| {
+                target < self.current_step || snapshot.step_index > self.current_step
+            })
+            .cloned();
+        if let Some(snapshot) = snapshot {
+            self.restore(snapshot);
+        }
+
+        while self.current_step < target {
+            let step = self.steps[self.current_step].clone();
+            self.execute_step(step);
+            self.current_step += 1;
+        }
+
+        self.pause_until = None;
+        self.next_step_delay = 0;
+        self.state = if self.current_step >= self.steps.len() {
+            AnimationState::Finished
+        } else {
+            AnimationState::Playing
+        };
+        let now = self.clock.now();
+        self.last_update = now;
+        self.last_frame = now;
+    }
+
+    /// Seeks to just after the next file is opened, or the one before the
+    /// open file when going backward. Returns false if there is none.
+    pub fn seek_file(&mut self, forward: bool) -> bool {
+        let mut opens = self
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| matches!(step, AnimationStep::SwitchFile { .. }))
+            .map(|(index, _)| index + 1);
+        let target = if forward {
+            opens.find(|&index| index > self.current_step)
+        } else {
+            opens.take_while(|&index| index < self.current_step).last()
+        };
+
+        match target {
+            Some(target) => {
+                self.seek(target);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Anchor of the position after the first `step` steps of the loaded
+    /// commit, or None before the first file is opened.
+    pub fn anchor(&self, step: usize) -> Option<EditAnchor> {
+        let (opened, file) = self.open_file_at(step)?;
+        let line_start = self
+            .line_starts
+            .iter()
+            .rev()
+            .find(|&&(start, ..)| start <= step && start > opened);
+        Some(match line_start {
+            Some(&(start, hunk, line)) => EditAnchor {
+                file,
+                hunk: Some(hunk),
+                line: Some(line),
+                offset: step - start,
+            },
+            None => EditAnchor {
+                file,
+                hunk: None,
+                line: None,
+                offset: step - opened - 1,
+            },
+        })
+    }
+
+    /// Step of the loaded commit that `anchor` points to, or None if the
+    /// commit doesn't edit its file or line.
+    pub fn anchor_step(&self, anchor: &EditAnchor) -> Option<usize> {
+        let base = match (anchor.hunk, anchor.line) {
+            (Some(hunk), Some(line)) => self
+                .line_starts
+                .iter()
+                .filter(|&&(_, h, l)| h == hunk && l == line)
+                .map(|&(start, ..)| start)
+                .find(|&start| {
+                    self.open_file_at(start).map(|(_, file)| file) == Some(anchor.file)
+                })?,
+            _ => {
+                self.steps.iter().position(|step| {
+                    matches!(step, AnimationStep::SwitchFile { file_index, .. } if *file_index == anchor.file)
+                })? + 1
+            }
+        };
+
+        // Typos change how many steps a line takes, so the offset stops
+        // short of the next line or file
+        let next_line = self
+            .line_starts
+            .iter()
+            .map(|&(start, ..)| start)
+            .find(|&start| start > base);
+        let next_file = self.steps[base..]
+            .iter()
+            .position(|step| matches!(step, AnimationStep::SwitchFile { .. }))
+            .map(|index| base + index);
+        let end = next_line
+            .into_iter()
+            .chain(next_file)
+            .min()
+            .unwrap_or(self.steps.len());
+        Some((base + anchor.offset).min(end))
+    }
+
+    // Last file opened within the first `step` steps, as the index of its
+    // `SwitchFile` step and its file index
+    fn open_file_at(&self, step: usize) -> Option<(usize, usize)> {
+        self.steps[..step.min(self.steps.len())]
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, step)| match step {
+                AnimationStep::SwitchFile { file_index, .. } => Some((index, *file_index)),
+                _ => None,
+            })
+    }
+
+    /// Repeats the steps in `range` of the loaded commit: playback seeks
+    /// back to its start whenever it reaches its end. Cleared when another
+    /// commit is loaded.
+    pub fn set_loop(&mut self, range: Option<Range<usize>>) {
+        self.loop_range = range
+            .map(|range| range.start..range.end.min(self.steps.len()))
+            .filter(|range| !range.is_empty());
+    }
+
+    pub fn loop_range(&self) -> Option<Range<usize>> {
+        self.loop_range.clone()
+    }
+
+    // Snapshots are taken the first time a step is reached. Memory stays
+    // bounded by thinning them out as the commit gets longer, which makes
+    // seeking replay more steps instead.
+    fn record_seek_snapshot(&mut self) {
+        let reached = self
+            .seek_snapshots
+            .last()
+            .is_some_and(|snapshot| snapshot.step_index >= self.current_step);
+        if reached || !self.current_step.is_multiple_of(self.seek_interval) {
+            return;
+        }
+
+        self.seek_snapshots.push(Snapshot::new(self));
+        if self.seek_snapshots.len() > MAX_SEEK_SNAPSHOTS {
+            self.seek_interval *= 2;
+            let interval = self.seek_interval;
+            self.seek_snapshots
+                .retain(|snapshot| snapshot.step_index.is_multiple_of(interval));
+        }
+    }
+
     fn is_boundary_step(step: &AnimationStep, mode: StepMode) -> bool {
         match mode {
             StepMode::Line => matches!(
@@ -453,12 +1002,14 @@ impl AnimationEngine {
                     | AnimationStep::TerminalPrompt
                     | AnimationStep::TerminalOutput { .. }
                     | AnimationStep::ResetState
+                    | AnimationStep::ShowSubmoduleCommit { .. }
             ),
             StepMode::Change => match step {
                 AnimationStep::SwitchFile { .. }
                 | AnimationStep::TerminalPrompt
                 | AnimationStep::TerminalOutput { .. }
-                | AnimationStep::ResetState => true,
+                | AnimationStep::ResetState
+                | AnimationStep::ShowSubmoduleCommit { .. } => true,
                 AnimationStep::Pause { multiplier } => Self::is_change_pause(*multiplier),
                 _ => false,
             },
@@ -467,22 +1018,16 @@ impl AnimationEngine {
 
     fn handle_step_checkpoint(&mut self, step: &AnimationStep) {
         match step {
-            AnimationStep::ResetState => {
-                self.clear_checkpoints();
+            AnimationStep::ResetState
+            | AnimationStep::SwitchFile { .. }
+            | AnimationStep::ShowSubmoduleCommit { .. } => {
                 self.record_checkpoint(CheckpointKind::Change);
                 self.record_checkpoint(CheckpointKind::Line);
             }
-            AnimationStep::SwitchFile { .. } => {
-                self.line_checkpoints.clear();
-                self.record_checkpoint(CheckpointKind::Change);
+            AnimationStep::Pause { multiplier } if self.active_pane == ActivePane::Editor => {
                 self.record_checkpoint(CheckpointKind::Line);
-            }
-            AnimationStep::Pause { multiplier } => {
-                if self.active_pane == ActivePane::Editor {
-                    self.record_checkpoint(CheckpointKind::Line);
-                    if Self::is_change_pause(*multiplier) {
-                        self.record_checkpoint(CheckpointKind::Change);
-                    }
+                if Self::is_change_pause(*multiplier) {
+                    self.record_checkpoint(CheckpointKind::Change);
                 }
             }
             _ => {}
@@ -493,42 +1038,13 @@ impl AnimationEngine {
         (multiplier - HUNK_PAUSE).abs() < f64::EPSILON
     }
 
+    // Called while a step executes, so the checkpoint resumes after it
     fn record_checkpoint(&mut self, kind: CheckpointKind) {
-        if self.current_step == 0 {
-            return;
-        }
-
-        let snapshot = ManualCheckpoint::new(self);
+        let step_index = self.current_step.saturating_add(1).min(self.steps.len());
         match kind {
-            CheckpointKind::Line => {
-                if self
-                    .line_checkpoints
-                    .back()
-                    .map(|c| c.step_index == snapshot.step_index)
-                    .unwrap_or(false)
-                {
-                    return;
-                }
-                self.line_checkpoints.push_back(snapshot);
-                if self.line_checkpoints.len() > MAX_LINE_CHECKPOINTS {
-                    self.line_checkpoints.pop_front();
-                }
-            }
-            CheckpointKind::Change => {
-                if self
-                    .change_checkpoints
-                    .back()
-                    .map(|c| c.step_index == snapshot.step_index)
-                    .unwrap_or(false)
-                {
-                    return;
-                }
-                self.change_checkpoints.push_back(snapshot);
-                if self.change_checkpoints.len() > MAX_CHANGE_CHECKPOINTS {
-                    self.change_checkpoints.pop_front();
-                }
-            }
-        }
+            CheckpointKind::Line => self.line_checkpoints.insert(step_index),
+            CheckpointKind::Change => self.change_checkpoints.insert(step_index),
+        };
     }
 
     fn clear_checkpoints(&mut self) {
@@ -541,9 +1057,143 @@ impl AnimationEngine {
         self.speed_rules = rules;
     }
 
+    /// Sets the model that types out code and terminal commands.
+    /// Takes effect from the next loaded commit.
+    pub fn set_typing_model(&mut self, model: Box<dyn TypingModel>) {
+        self.typing = model;
+    }
+
+    /// Builds the typing model from settings, seeded from the engine's
+    /// generator, and applies the autocomplete setting.
+    pub fn set_typing_settings(&mut self, settings: &TypingSettings) {
+        let seed = self.rng.random();
+        self.set_typing_model(settings.build(seed));
+        self.autocomplete = settings.autocomplete;
+    }
+
+    /// Sets the source of time, e.g. a `ManualClock` to play faster than
+    /// real time. Timers restart from the new clock's current time.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        let now = clock.now();
+        self.clock = clock;
+        self.last_update = now;
+        self.last_frame = now;
+        self.cursor_blink_timer = now;
+        self.pause_until = None;
+    }
+
+    /// Sets the generator behind hunk shuffling and typing variation, so that
+    /// the same seed replays a commit with the same steps and timings.
+    /// Call before `set_typing_settings`, which seeds the typing model from it.
+    pub fn set_rng(&mut self, rng: StdRng) {
+        self.rng = rng;
+    }
+
+    /// Sets the order in which hunks are edited.
+    pub fn set_hunk_order(&mut self, order: HunkOrder) {
+        self.hunk_order = order;
+    }
+
+    /// Sets how many times faster than their typing speed steps play. The
+    /// delay and pause in progress are rescaled to the new rate.
+    pub fn set_playback_rate(&mut self, rate: f64) {
+        let previous = self.effective_rate();
+        self.playback_rate = rate;
+        self.rescale_delays(previous);
+    }
+
+    /// Speed-up set with `set_playback_rate`.
+    pub fn playback_rate(&self) -> f64 {
+        self.playback_rate
+    }
+
+    /// Plays the rest of the open file at a much higher rate, until the
+    /// next file is opened. Returns false if no file is being edited.
+    pub fn fast_forward_file(&mut self) -> bool {
+        if self.fast_forward
+            || self.active_pane != ActivePane::Editor
+            || self.current_file_path.is_none()
+        {
+            return false;
+        }
+        let previous = self.effective_rate();
+        self.fast_forward = true;
+        self.rescale_delays(previous);
+        true
+    }
+
+    /// Current speed-up, including fast-forwarding.
+    pub fn effective_rate(&self) -> f64 {
+        if self.fast_forward {
+            self.playback_rate * FAST_FORWARD_RATE
+        } else {
+            self.playback_rate
+        }
+    }
+
+    // Delay in milliseconds of something that takes `ms` at normal speed
+    fn scaled(&self, ms: f64) -> u64 {
+        (ms * self.commit_scale / self.effective_rate()) as u64
+    }
+
+    fn rescale_delays(&mut self, previous_rate: f64) {
+        let factor = previous_rate / self.effective_rate();
+        self.next_step_delay = (self.next_step_delay as f64 * factor) as u64;
+        if let Some(pause_until) = self.pause_until {
+            let now = self.clock.now();
+            let remaining = pause_until.saturating_duration_since(now);
+            self.pause_until = Some(now + remaining.mul_f64(factor));
+        }
+    }
+
+    /// Fits each commit loaded from now on into `duration`, by scaling its
+    /// typing speed and pauses.
+    pub fn set_commit_duration(&mut self, duration: Option<CommitDuration>) {
+        self.commit_duration = duration;
+    }
+
+    /// Factor that the delays of the loaded commit are scaled by.
+    pub fn commit_scale(&self) -> f64 {
+        self.commit_scale
+    }
+
+    // How long the generated steps take at the configured speeds, following
+    // the timing of `execute_step`. The delay after the last step is never
+    // waited for.
+    fn estimate_duration(&self) -> Duration {
+        let mut speed = self.speed_ms as f64;
+        let mut total = 0.0;
+        for step in &self.steps[..self.steps.len().saturating_sub(1)] {
+            total += match step {
+                AnimationStep::InsertChar { delay, .. }
+                | AnimationStep::DeleteChar { delay, .. }
+                | AnimationStep::DialogTypeChar { delay, .. }
+                | AnimationStep::TerminalTypeChar { delay, .. }
+                | AnimationStep::TerminalBackspace { delay } => speed * delay,
+                AnimationStep::Pause { multiplier } => speed * multiplier,
+                _ => speed,
+            };
+            if let AnimationStep::SwitchFile { path, .. } = step {
+                speed = self.speed_for_file(path) as f64;
+            }
+        }
+        Duration::from_secs_f64(total / 1000.0)
+    }
+
+    /// Typing speed for files that match no speed rule.
+    pub fn base_speed(&self) -> u64 {
+        self.base_speed_ms
+    }
+
+    /// Sets the typing speed for files that match no speed rule.
+    pub fn set_base_speed(&mut self, speed_ms: u64) {
+        self.base_speed_ms = speed_ms;
+        self.speed_ms = speed_ms;
+    }
+
     /// Get the speed for a given file path based on speed rules
     /// Returns the first matching rule's speed, or the base speed if no match
-    fn get_speed_for_file(&self, path: &str) -> u64 {
+    pub fn speed_for_file(&self, path: &str) -> u64 {
         for rule in &self.speed_rules {
             if rule.matches(path) {
                 return rule.speed_ms;
@@ -562,9 +1212,31 @@ impl AnimationEngine {
         self.content_width = width;
     }
 
+    /// Preview of the open file, if it is a binary file or an image
+    pub fn current_binary(&self) -> Option<&BinaryChange> {
+        self.current_file_path.as_ref()?;
+        self.current_metadata()?
+            .changes
+            .get(self.current_file_index)?
+            .binary
+            .as_ref()
+    }
+
+    /// Steps generated for the loaded commit.
+    pub fn steps(&self) -> &[AnimationStep] {
+        &self.steps
+    }
+
+    /// Submodule commits that `ShowSubmoduleCommit` steps refer to by index.
+    pub fn submodule_commits(&self) -> &[CommitMetadata] {
+        &self.submodule_commits
+    }
+
     /// Get the current metadata being displayed
     pub fn current_metadata(&self) -> Option<&CommitMetadata> {
-        self.current_metadata.as_ref()
+        self.submodule_commit
+            .and_then(|index| self.submodule_commits.get(index))
+            .or(self.current_metadata.as_deref())
     }
 
     fn calculate_line_offsets(content: &str) -> Vec<usize> {
@@ -582,20 +1254,34 @@ impl AnimationEngine {
     /// Add a terminal command with typing animation
     fn add_terminal_command(&mut self, command: &str) {
         self.steps.push(AnimationStep::TerminalPrompt);
-        for ch in command.chars() {
-            self.steps.push(AnimationStep::TerminalTypeChar { ch });
+        for keystroke in self.typing.keystrokes(command) {
+            self.steps.push(match keystroke.key {
+                Key::Char(ch) => AnimationStep::TerminalTypeChar {
+                    ch,
+                    delay: keystroke.delay,
+                },
+                Key::Backspace => AnimationStep::TerminalBackspace {
+                    delay: keystroke.delay,
+                },
+            });
         }
     }
 
     /// Load a commit and generate animation steps
     pub fn load_commit(&mut self, metadata: &CommitMetadata) {
         // Store pending metadata to be applied on ResetState
-        self.pending_metadata = Some(metadata.clone());
+        self.pending_metadata = Some(Arc::new(metadata.clone()));
 
         self.steps.clear();
+        self.line_starts.clear();
+        self.loop_range = None;
+        self.seek_snapshots.clear();
+        self.seek_interval = SEEK_SNAPSHOT_INTERVAL;
+        self.submodule_commits.clear();
+        self.submodule_commit = None;
         self.current_step = 0;
         self.state = AnimationState::Playing;
-        self.last_update = Instant::now();
+        self.last_update = self.clock.now();
         self.pause_until = None;
 
         // Check if this is a working tree diff (not a real commit)
@@ -621,6 +1307,14 @@ impl AnimationEngine {
                 multiplier: CHECKOUT_OUTPUT_PAUSE,
             });
         } else {
+            // Change to the repository when playing several
+            if let Some(repository) = &metadata.repository {
+                self.add_terminal_command(&format!("cd ~/{}", repository));
+                self.steps.push(AnimationStep::Pause {
+                    multiplier: CHECKOUT_PAUSE,
+                });
+            }
+
             // Time travel to commit date
             let datetime_str = metadata.date.format("%Y-%m-%d %H:%M:%S").to_string();
             self.add_terminal_command(&format!("time-travel {}", datetime_str));
@@ -657,38 +1351,136 @@ impl AnimationEngine {
         // Apply new metadata after intro animation
         self.steps.push(AnimationStep::ResetState);
 
-        // Sort file changes to match FileTree display order (directory -> filename)
-        let sorted_indices = metadata.sorted_file_indices();
+        self.add_file_steps(metadata);
 
-        // Process all file changes in sorted order
-        for &index in &sorted_indices {
-            let change = &metadata.changes[index];
-            match (change.is_excluded, &change.status) {
-                // Skip excluded files (lock files and generated files)
-                (true, _) => {
-                    // Switch to the excluded file to show in file tree
-                    let old_content = change.old_content.clone().unwrap_or_default();
-                    let new_content = change.new_content.clone().unwrap_or_default();
-                    self.steps.push(AnimationStep::SwitchFile {
-                        file_index: index,
-                        old_content,
-                        new_content,
-                        path: change.path.clone(),
-                    });
+        // Skip git commit/push animation for working tree diffs
+        if is_working_tree {
+            // Just add a final pause for working tree mode
+            self.steps.push(AnimationStep::Pause {
+                multiplier: PUSH_FINAL_PAUSE,
+            });
+        } else {
+            // Git commit
+            let parent_hash = format!("{}^", &metadata.hash[..7]);
+            let commit_message = metadata.message.lines().next().unwrap_or("Update");
+            self.add_terminal_command(&format!("git commit -m \"{}\"", commit_message));
+            self.steps.push(AnimationStep::Pause {
+                multiplier: GIT_COMMIT_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: format!("💾 [main {}] {}", &metadata.hash[..7], commit_message),
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: format!(
+                    "📝 {} file{} changed - immortalized forever!",
+                    metadata.changes.len(),
+                    if metadata.changes.len() == 1 { "" } else { "s" }
+                ),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: COMMIT_OUTPUT_PAUSE,
+            });
 
-                    self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_FILE_PAUSE,
-                    });
-                    let reason = change
-                        .exclusion_reason
-                        .as_deref()
-                        .unwrap_or("excluded file");
-                    self.steps.push(AnimationStep::TerminalOutput {
-                        text: format!("📦 {} (skipped - {})", change.path, reason),
-                    });
-                    self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_CMD_PAUSE,
-                    });
+            // Git push
+            self.add_terminal_command("git push origin main");
+            self.steps.push(AnimationStep::Pause {
+                multiplier: GIT_PUSH_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: "🚀 Launching code into the cloud...".to_string(),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: PUSH_OUTPUT_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: "📦 Compressing digital dreams: 100% (5/5)".to_string(),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: PUSH_OUTPUT_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: "✍️  Signing with invisible ink: done.".to_string(),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: GIT_PUSH_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: "📡 Beaming to origin/main via satellite...".to_string(),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: PUSH_OUTPUT_PAUSE,
+            });
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: format!(
+                    "   {}..{} ✨ SUCCESS",
+                    &parent_hash[..7],
+                    &metadata.hash[..7]
+                ),
+            });
+            self.steps.push(AnimationStep::Pause {
+                multiplier: PUSH_FINAL_PAUSE,
+            });
+        }
+
+        // Start with empty editor (no file opened yet)
+        self.buffer = EditorBuffer::new();
+        self.clear_checkpoints();
+        self.commit_scale = self
+            .commit_duration
+            .map_or(1.0, |duration| duration.scale(self.estimate_duration()));
+    }
+
+    /// Generate the steps that open and edit each changed file of a commit
+    fn add_file_steps(&mut self, metadata: &CommitMetadata) {
+        // Sort file changes to match FileTree display order (directory -> filename)
+        let sorted_indices = metadata.sorted_file_indices();
+        let groups = if self.hunk_order == HunkOrder::Interleave {
+            Self::related_files(metadata, &sorted_indices)
+        } else {
+            Vec::new()
+        };
+
+        // Process all file changes in sorted order
+        for &index in &sorted_indices {
+            let change = &metadata.changes[index];
+            if let Some(group) = groups.iter().find(|group| group.contains(&index)) {
+                // Related files are edited together when the first of them comes up
+                if group[0] == index {
+                    self.add_interleaved_steps(metadata, group);
+                }
+                continue;
+            }
+            match (change.is_excluded, &change.status) {
+                // Skip excluded files (lock files and generated files)
+                (true, _) => {
+                    // Switch to the excluded file to show in file tree
+                    let old_content = change.old_content.clone().unwrap_or_default();
+                    let new_content = change.new_content.clone().unwrap_or_default();
+                    self.steps.push(AnimationStep::SwitchFile {
+                        file_index: index,
+                        old_content,
+                        new_content,
+                        path: change.path.clone(),
+                        syntax_path: change.syntax_path.clone(),
+                    });
+
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: OPEN_FILE_PAUSE,
+                    });
+                    if change.dependency_changes.is_empty() {
+                        let reason = change
+                            .exclusion_reason
+                            .as_deref()
+                            .unwrap_or("excluded file");
+                        self.steps.push(AnimationStep::TerminalOutput {
+                            text: format!("📦 {} (skipped - {})", change.path, reason),
+                        });
+                    } else {
+                        self.add_dependency_summary(change);
+                    }
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: OPEN_CMD_PAUSE,
+                    });
                 }
                 // For deleted files, skip editor animation and only run rm + git add
                 (false, FileStatus::Deleted) => {
@@ -699,6 +1491,7 @@ impl AnimationEngine {
                         old_content,
                         new_content: String::new(),
                         path: change.path.clone(),
+                        syntax_path: change.syntax_path.clone(),
                     });
 
                     self.steps.push(AnimationStep::Pause {
@@ -723,6 +1516,7 @@ impl AnimationEngine {
                         old_content,
                         new_content,
                         path: change.path.clone(),
+                        syntax_path: change.syntax_path.clone(),
                     });
 
                     self.steps.push(AnimationStep::Pause {
@@ -739,45 +1533,49 @@ impl AnimationEngine {
                         multiplier: GIT_ADD_CMD_PAUSE,
                     });
                 }
-                // Normal files (Added, Modified, etc.) - full editor animation
-                (false, _) => {
-                    // Open file in editor
-                    if index == 0 {
-                        self.steps.push(AnimationStep::Pause {
-                            multiplier: OPEN_FILE_FIRST_PAUSE,
-                        });
-                    } else {
-                        self.steps.push(AnimationStep::Pause {
-                            multiplier: OPEN_FILE_PAUSE,
-                        });
-                    }
-                    // Show "Open File..." dialog and type the file path
-                    self.steps.push(AnimationStep::OpenFileDialogStart);
-                    self.steps.push(AnimationStep::Pause { multiplier: 5.0 });
-
-                    // Type each character of the file path
-                    for ch in change.path.chars() {
-                        self.steps.push(AnimationStep::DialogTypeChar { ch });
-                    }
-
+                // Submodule pointer changes list the commits they bring in
+                (false, _) if change.submodule.is_some() => {
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_CMD_PAUSE,
+                        multiplier: OPEN_FILE_PAUSE,
                     });
-
-                    // Add file switch step with both old and new content
-                    let old_content = change.old_content.clone().unwrap_or_default();
-                    let new_content = change.new_content.clone().unwrap_or_default();
                     self.steps.push(AnimationStep::SwitchFile {
                         file_index: index,
-                        old_content,
-                        new_content,
+                        old_content: String::new(),
+                        new_content: String::new(),
                         path: change.path.clone(),
+                        syntax_path: None,
                     });
-
-                    // Add pause before starting file animation
+                    self.add_submodule_steps(change);
+                    self.add_terminal_command(&format!("git add {}", change.path));
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: FILE_SWITCH_PAUSE,
+                        multiplier: GIT_ADD_CMD_PAUSE,
                     });
+                }
+                // Binary files and images are previewed in the editor instead of typed
+                (false, _) if change.binary.is_some() => {
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: OPEN_FILE_PAUSE,
+                    });
+                    self.steps.push(AnimationStep::SwitchFile {
+                        file_index: index,
+                        old_content: String::new(),
+                        new_content: String::new(),
+                        path: change.path.clone(),
+                        syntax_path: None,
+                    });
+                    self.add_binary_summary(change);
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: BINARY_PREVIEW_PAUSE,
+                    });
+                    self.add_terminal_command(&format!("git add {}", change.path));
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: GIT_ADD_CMD_PAUSE,
+                    });
+                }
+                // Normal files (Added, Modified, etc.) - full editor animation
+                (false, _) => {
+                    let old_content = change.old_content.clone().unwrap_or_default();
+                    self.add_open_file_steps(index, change, old_content);
 
                     // Generate animation steps for this file
                     self.generate_steps_for_file(change);
@@ -793,137 +1591,365 @@ impl AnimationEngine {
                 }
             }
         }
+    }
 
-        // Skip git commit/push animation for working tree diffs
-        if is_working_tree {
-            // Just add a final pause for working tree mode
+    /// Open a file through the "Open File..." dialog, showing `old_content`
+    /// in the editor
+    fn add_open_file_steps(&mut self, index: usize, change: &FileChange, old_content: String) {
+        if index == 0 {
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_FINAL_PAUSE,
+                multiplier: OPEN_FILE_FIRST_PAUSE,
             });
         } else {
-            // Git commit
-            let parent_hash = format!("{}^", &metadata.hash[..7]);
-            let commit_message = metadata.message.lines().next().unwrap_or("Update");
-            self.add_terminal_command(&format!("git commit -m \"{}\"", commit_message));
             self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_COMMIT_PAUSE,
+                multiplier: OPEN_FILE_PAUSE,
             });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!("💾 [main {}] {}", &metadata.hash[..7], commit_message),
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!(
-                    "📝 {} file{} changed - immortalized forever!",
-                    metadata.changes.len(),
-                    if metadata.changes.len() == 1 { "" } else { "s" }
-                ),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: COMMIT_OUTPUT_PAUSE,
+        }
+        // Show "Open File..." dialog and type the file path
+        self.steps.push(AnimationStep::OpenFileDialogStart);
+        self.steps.push(AnimationStep::Pause { multiplier: 5.0 });
+
+        // Type each character of the file path
+        for ch in change.path.chars() {
+            // Dialog typing is slower (2x speed with variation)
+            let delay = 2.0 * self.rng.random_range(0.7..=1.3);
+            self.steps.push(AnimationStep::DialogTypeChar { ch, delay });
+        }
+
+        self.steps.push(AnimationStep::Pause {
+            multiplier: OPEN_CMD_PAUSE,
+        });
+
+        // Add file switch step with both old and new content
+        let new_content = change.new_content.clone().unwrap_or_default();
+        self.steps.push(AnimationStep::SwitchFile {
+            file_index: index,
+            old_content,
+            new_content,
+            path: change.path.clone(),
+            syntax_path: change.syntax_path.clone(),
+        });
+
+        // Add pause before starting file animation
+        self.steps.push(AnimationStep::Pause {
+            multiplier: FILE_SWITCH_PAUSE,
+        });
+    }
+
+    /// Groups of related files whose hunks are interleaved: files in the same
+    /// directory or with the same name, in display order
+    fn related_files(metadata: &CommitMetadata, sorted_indices: &[usize]) -> Vec<Vec<usize>> {
+        let directory = |path: &str| path.rsplit_once('/').map_or("", |(dir, _)| dir).to_string();
+        let stem = |path: &str| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            name.split('.').next().unwrap_or(name).to_string()
+        };
+
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for &index in sorted_indices {
+            let change = &metadata.changes[index];
+            let is_edited = !change.is_excluded
+                && !matches!(change.status, FileStatus::Deleted | FileStatus::Renamed)
+                && change.submodule.is_none()
+                && change.binary.is_none()
+                && !change.hunks.is_empty();
+            if !is_edited {
+                continue;
+            }
+
+            let related = groups.iter_mut().find(|group| {
+                group.len() < MAX_INTERLEAVED_FILES
+                    && group.iter().any(|&other| {
+                        let other = &metadata.changes[other].path;
+                        directory(other) == directory(&change.path)
+                            || stem(other) == stem(&change.path)
+                    })
             });
+            match related {
+                Some(group) => group.push(index),
+                None => groups.push(vec![index]),
+            }
+        }
 
-            // Git push
-            self.add_terminal_command("git push origin main");
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Edit related files together, switching to the next file after each
+    /// hunk and reopening files with the hunks applied so far
+    fn add_interleaved_steps(&mut self, metadata: &CommitMetadata, group: &[usize]) {
+        let mut files: Vec<(usize, FileProgress)> = group
+            .iter()
+            .map(|&index| (index, self.start_file(&metadata.changes[index])))
+            .collect();
+
+        while files.iter().any(|(_, file)| !file.pending.is_empty()) {
+            for (index, file) in &mut files {
+                if file.pending.is_empty() {
+                    continue;
+                }
+                // Content with the hunks applied so far, in file order
+                let mut applied: Vec<&DiffHunk> = file
+                    .applied
+                    .iter()
+                    .map(|&i| &file.change.hunks[i])
+                    .collect();
+                applied.sort_by_key(|hunk| hunk.old_start);
+                let old_content = file.change.old_content.as_deref().unwrap_or_default();
+                let content = apply_hunks(old_content, &applied);
+                self.add_open_file_steps(*index, file.change, content);
+                file.cursor_line = 0;
+                self.generate_next_hunk(file);
+            }
+        }
+
+        for (_, file) in &files {
             self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_PUSH_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "🚀 Launching code into the cloud...".to_string(),
+                multiplier: GIT_ADD_PAUSE,
             });
+            self.add_terminal_command(&format!("git add {}", file.change.path));
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
+                multiplier: GIT_ADD_CMD_PAUSE,
             });
+        }
+    }
+
+    /// List the commits a submodule pointer change brings in, then replay the
+    /// latest of them as nested commits
+    fn add_submodule_steps(&mut self, change: &FileChange) {
+        let Some(submodule) = &change.submodule else {
+            return;
+        };
+        let short = |id: &str| id[..id.len().min(7)].to_string();
+
+        let (old_id, new_id) = match (&submodule.old_id, &submodule.new_id) {
+            (Some(old_id), Some(new_id)) => (old_id, new_id),
+            (None, Some(new_id)) => {
+                self.steps.push(AnimationStep::TerminalOutput {
+                    text: format!("🔗 {} (submodule added at {})", change.path, short(new_id)),
+                });
+                return;
+            }
+            (Some(_), None) => {
+                self.steps.push(AnimationStep::TerminalOutput {
+                    text: format!("🔗 {} (submodule removed)", change.path),
+                });
+                return;
+            }
+            (None, None) => return,
+        };
+
+        let Some(commits) = &submodule.commits else {
             self.steps.push(AnimationStep::TerminalOutput {
-                text: "📦 Compressing digital dreams: 100% (5/5)".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
+                text: format!(
+                    "🔗 {} {}..{} (submodule commits not available locally)",
+                    change.path,
+                    short(old_id),
+                    short(new_id)
+                ),
             });
+            return;
+        };
+
+        self.add_terminal_command(&format!(
+            "git -C {} log --oneline {}..{}",
+            change.path,
+            short(old_id),
+            short(new_id)
+        ));
+        self.steps.push(AnimationStep::Pause {
+            multiplier: GIT_ADD_CMD_PAUSE,
+        });
+        for commit in commits {
             self.steps.push(AnimationStep::TerminalOutput {
-                text: "✍️  Signing with invisible ink: done.".to_string(),
+                text: format!("{} {}", short(&commit.hash), commit.summary),
             });
             self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_PUSH_PAUSE,
+                multiplier: DEPENDENCY_LINE_PAUSE,
             });
+        }
+        if submodule.total_commits > commits.len() {
             self.steps.push(AnimationStep::TerminalOutput {
-                text: "📡 Beaming to origin/main via satellite...".to_string(),
+                text: format!("… and {} more", submodule.total_commits - commits.len()),
             });
+        }
+
+        if submodule.replays.is_empty() {
+            return;
+        }
+
+        self.add_terminal_command(&format!("cd {}", change.path));
+        for replay in &submodule.replays {
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
+                multiplier: CHECKOUT_OUTPUT_PAUSE,
+            });
+            self.steps.push(AnimationStep::ShowSubmoduleCommit {
+                index: Some(self.submodule_commits.len()),
             });
             self.steps.push(AnimationStep::TerminalOutput {
                 text: format!(
-                    "   {}..{} ✨ SUCCESS",
-                    &parent_hash[..7],
-                    &metadata.hash[..7]
+                    "↳ {} {}",
+                    short(&replay.hash),
+                    replay.message.lines().next().unwrap_or_default()
                 ),
             });
+            self.submodule_commits.push(replay.clone());
+            self.add_file_steps(replay);
+        }
+        self.steps
+            .push(AnimationStep::ShowSubmoduleCommit { index: None });
+        self.add_terminal_command("cd -");
+        self.steps.push(AnimationStep::Pause {
+            multiplier: GIT_ADD_PAUSE,
+        });
+    }
+
+    /// List the package changes of a lock file in the terminal
+    fn add_dependency_summary(&mut self, change: &FileChange) {
+        let count = change.dependency_changes.len();
+        self.steps.push(AnimationStep::TerminalOutput {
+            text: format!(
+                "📦 {} ({} dependenc{} changed)",
+                change.path,
+                count,
+                if count == 1 { "y" } else { "ies" }
+            ),
+        });
+
+        for dependency in change.dependency_changes.iter().take(MAX_DEPENDENCY_LINES) {
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: format!("   {}", dependency),
+            });
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_FINAL_PAUSE,
+                multiplier: DEPENDENCY_LINE_PAUSE,
             });
         }
+        if count > MAX_DEPENDENCY_LINES {
+            self.steps.push(AnimationStep::TerminalOutput {
+                text: format!("   … and {} more", count - MAX_DEPENDENCY_LINES),
+            });
+        }
+    }
 
-        // Start with empty editor (no file opened yet)
-        self.buffer = EditorBuffer::new();
-        self.clear_checkpoints();
+    /// Report the size change of a binary file in the terminal
+    fn add_binary_summary(&mut self, change: &FileChange) {
+        let Some(binary) = &change.binary else {
+            return;
+        };
+        let text = match binary.dimension_change().filter(|_| binary.is_image()) {
+            Some(dimensions) => format!(
+                "🎨 {} ({}, {})",
+                change.path,
+                dimensions,
+                binary.size_change()
+            ),
+            None => format!("🧩 {} (binary, {})", change.path, binary.size_change()),
+        };
+        self.steps.push(AnimationStep::TerminalOutput { text });
     }
 
     /// Generate animation steps for a file change
     fn generate_steps_for_file(&mut self, change: &FileChange) {
-        let mut current_cursor_line = 0;
-        let mut line_offset = 0i64; // Track how buffer lines differ from old file
-
-        // Parse old_content into lines for indentation calculation during cursor movement
-        let old_lines: Vec<&str> = change
-            .old_content
-            .as_ref()
-            .map(|c| c.lines().collect())
-            .unwrap_or_default();
-
-        // Process each hunk
-        for hunk in &change.hunks {
-            // Calculate target line in current buffer
-            // hunk.old_start is 1-indexed (Git line numbers start at 1)
-            // We need to convert to 0-indexed and adjust by how many lines we've added/removed
-            let target_line = ((hunk.old_start as i64) - 1 + line_offset).max(0) as usize;
-
-            // Calculate distance for speed adjustment
-            let distance = target_line.abs_diff(current_cursor_line);
-
-            current_cursor_line = self.generate_cursor_movement(
-                current_cursor_line,
-                target_line,
-                distance,
-                &old_lines,
-            );
-
-            let (final_cursor_line, _final_buffer_line) =
-                self.generate_steps_for_hunk(hunk, current_cursor_line, target_line);
-
-            current_cursor_line = final_cursor_line;
-
-            // Update offset based on changes in this hunk
-            // Count additions and deletions to update the offset
-            let additions = hunk
-                .lines
-                .iter()
-                .filter(|l| matches!(l.change_type, LineChangeType::Addition))
-                .count() as i64;
-            let deletions = hunk
-                .lines
-                .iter()
-                .filter(|l| matches!(l.change_type, LineChangeType::Deletion))
-                .count() as i64;
+        let mut file = self.start_file(change);
+        while self.generate_next_hunk(&mut file) {}
+    }
 
-            line_offset += additions - deletions;
+    /// Plan the editing of a file in the configured hunk order
+    fn start_file<'a>(&mut self, change: &'a FileChange) -> FileProgress<'a> {
+        let mut pending: Vec<usize> = (0..change.hunks.len()).collect();
+        match self.hunk_order {
+            HunkOrder::TopDown | HunkOrder::Interleave => {}
+            HunkOrder::BottomUp => pending.reverse(),
+            HunkOrder::SignatureFirst => {
+                pending.sort_by_key(|&i| !is_signature_hunk(&change.hunks[i]));
+            }
+            HunkOrder::Random => pending.shuffle(&mut self.rng),
+        }
 
-            // Add pause between hunks
-            self.steps.push(AnimationStep::Pause {
-                multiplier: HUNK_PAUSE,
-            });
+        let completer = self.autocomplete.then(|| {
+            Completer::new(
+                self.highlighter.get_mut(),
+                change.syntax_path.as_deref().unwrap_or(&change.path),
+                change.old_content.as_deref().unwrap_or_default(),
+                change.new_content.as_deref().unwrap_or_default(),
+            )
+        });
+
+        FileProgress {
+            change,
+            pending: pending.into(),
+            applied: Vec::new(),
+            line_deltas: Vec::new(),
+            old_lines: change
+                .old_content
+                .as_deref()
+                .map(|content| content.lines().collect())
+                .unwrap_or_default(),
+            cursor_line: 0,
+            completer,
         }
     }
 
+    /// Generate the steps of the next hunk of a file, returning false when
+    /// every hunk has been edited
+    fn generate_next_hunk(&mut self, file: &mut FileProgress) -> bool {
+        let Some(index) = file.pending.pop_front() else {
+            return false;
+        };
+        let hunk = &file.change.hunks[index];
+
+        // Calculate target line in current buffer
+        // hunk.old_start is 1-indexed (Git line numbers start at 1)
+        // We need to convert to 0-indexed and adjust by how many lines the
+        // hunks already edited above this one have added or removed
+        // A hunk without old lines (pure insertion, no context) inserts after old_start
+        let hunk_start = if hunk.old_lines == 0 {
+            hunk.old_start as i64
+        } else {
+            hunk.old_start as i64 - 1
+        };
+        let line_offset: i64 = file
+            .line_deltas
+            .iter()
+            .filter(|(old_start, _)| *old_start < hunk.old_start)
+            .map(|(_, delta)| delta)
+            .sum();
+        let target_line = (hunk_start + line_offset).max(0) as usize;
+
+        // Calculate distance for speed adjustment
+        let distance = target_line.abs_diff(file.cursor_line);
+
+        let cursor_line =
+            self.generate_cursor_movement(file.cursor_line, target_line, distance, &file.old_lines);
+
+        let (final_cursor_line, _final_buffer_line) = self.generate_steps_for_hunk(
+            index,
+            hunk,
+            cursor_line,
+            target_line,
+            file.completer.as_mut(),
+        );
+
+        file.cursor_line = final_cursor_line;
+        file.applied.push(index);
+        let delta = hunk
+            .lines
+            .iter()
+            .map(|line| match line.change_type {
+                LineChangeType::Addition => 1,
+                LineChangeType::Deletion => -1,
+                LineChangeType::Context => 0,
+            })
+            .sum();
+        file.line_deltas.push((hunk.old_start, delta));
+
+        // Add pause between hunks
+        self.steps.push(AnimationStep::Pause {
+            multiplier: HUNK_PAUSE,
+        });
+        true
+    }
+
     /// Generate cursor movement steps from current line to target line
     fn generate_cursor_movement(
         &mut self,
@@ -1010,15 +2036,19 @@ impl AnimationEngine {
     /// Returns (final_cursor_line, final_buffer_line)
     fn generate_steps_for_hunk(
         &mut self,
+        hunk_index: usize,
         hunk: &DiffHunk,
         start_cursor_line: usize,
         start_buffer_line: usize,
+        mut completer: Option<&mut Completer>,
     ) -> (usize, usize) {
         // buffer_line tracks the actual line number in the current buffer
         let mut buffer_line = start_buffer_line;
         let mut cursor_line = start_cursor_line;
 
-        for line_change in &hunk.lines {
+        for (line_index, line_change) in hunk.lines.iter().enumerate() {
+            self.line_starts
+                .push((self.steps.len(), hunk_index, line_index));
             match line_change.change_type {
                 LineChangeType::Deletion => {
                     // Delete the entire line at current buffer position
@@ -1040,15 +2070,54 @@ impl AnimationEngine {
                     self.steps.push(AnimationStep::InsertLine {
                         line: buffer_line,
                         content: indentation,
+                        line_ending: line_change.line_ending,
                     });
 
-                    // Type each character after the indentation
-                    for (i, ch) in content.chars().skip(indentation_len).enumerate() {
-                        self.steps.push(AnimationStep::InsertChar {
-                            line: buffer_line,
-                            col: indentation_len + i,
-                            ch,
-                        });
+                    // Type the rest of the line after the indentation
+                    let text: String = content.chars().skip(indentation_len).collect();
+                    let chunks = match completer.as_deref_mut() {
+                        Some(completer) => completer.chunks(&text),
+                        None => vec![Chunk::Type(text)],
+                    };
+                    let mut col = indentation_len;
+                    for chunk in chunks {
+                        match chunk {
+                            Chunk::Type(text) => {
+                                col = self.add_typing_steps(buffer_line, col, &text);
+                            }
+                            Chunk::Complete {
+                                text,
+                                candidates,
+                                selected,
+                            } => {
+                                // The popup opens right after the prefix, without
+                                // the pause the typing model adds at the end of text
+                                if let Some(AnimationStep::InsertChar { delay, .. }) =
+                                    self.steps.last_mut()
+                                {
+                                    *delay = delay.min(1.0);
+                                }
+                                let prefix_len = candidates
+                                    .get(selected)
+                                    .map_or(0, |c| c.chars().count() - text.chars().count());
+                                self.steps.push(AnimationStep::ShowCompletion {
+                                    line: buffer_line,
+                                    col: col - prefix_len,
+                                    candidates,
+                                    selected,
+                                });
+                                self.steps.push(AnimationStep::Pause {
+                                    multiplier: COMPLETION_PAUSE,
+                                });
+                                let len = text.chars().count();
+                                self.steps.push(AnimationStep::AcceptCompletion {
+                                    line: buffer_line,
+                                    col,
+                                    text,
+                                });
+                                col += len;
+                            }
+                        }
                     }
 
                     cursor_line = buffer_line;
@@ -1084,6 +2153,32 @@ impl AnimationEngine {
         (cursor_line, buffer_line)
     }
 
+    /// Type `text` into the buffer at `line`, returning the column after it
+    fn add_typing_steps(&mut self, line: usize, mut col: usize, text: &str) -> usize {
+        for keystroke in self.typing.keystrokes(text) {
+            match keystroke.key {
+                Key::Char(ch) => {
+                    self.steps.push(AnimationStep::InsertChar {
+                        line,
+                        col,
+                        ch,
+                        delay: keystroke.delay,
+                    });
+                    col += 1;
+                }
+                Key::Backspace => {
+                    col -= 1;
+                    self.steps.push(AnimationStep::DeleteChar {
+                        line,
+                        col,
+                        delay: keystroke.delay,
+                    });
+                }
+            }
+        }
+        col
+    }
+
     /// Updates animation state and returns true if display needs refresh.
     pub fn tick(&mut self) -> bool {
         self.update_cursor_blink();
@@ -1100,13 +2195,19 @@ impl AnimationEngine {
             return false;
         }
 
-        let now = Instant::now();
+        let now = self.clock.now();
         if !self.should_render_frame(now) {
             return false;
         }
 
         let executed = self.execute_batch_steps(now);
 
+        if let Some(range) = self.loop_range.clone() {
+            if self.current_step >= range.end {
+                self.seek(range.start);
+            }
+        }
+
         if self.current_step >= self.steps.len() {
             self.state = AnimationState::Finished;
         }
@@ -1115,15 +2216,16 @@ impl AnimationEngine {
     }
 
     fn update_cursor_blink(&mut self) {
-        if self.cursor_blink_timer.elapsed() >= Duration::from_millis(500) {
+        let now = self.clock.now();
+        if now.duration_since(self.cursor_blink_timer) >= Duration::from_millis(500) {
             self.cursor_visible = !self.cursor_visible;
-            self.cursor_blink_timer = Instant::now();
+            self.cursor_blink_timer = now;
         }
     }
 
     fn is_paused(&mut self) -> bool {
         if let Some(pause_until) = self.pause_until {
-            if Instant::now() < pause_until {
+            if self.clock.now() < pause_until {
                 return true;
             }
             self.pause_until = None;
@@ -1154,7 +2256,7 @@ impl AnimationEngine {
         }
 
         if executed_any {
-            self.last_update = Instant::now();
+            self.last_update = self.clock.now();
             self.last_frame = frame_start;
         }
 
@@ -1164,7 +2266,8 @@ impl AnimationEngine {
     fn can_execute_step(&self, executed_any: bool, accumulated_delay: u64) -> bool {
         // First step: check if enough time has elapsed since last step
         if !executed_any {
-            return self.last_update.elapsed() >= Duration::from_millis(self.next_step_delay);
+            return self.clock.now().duration_since(self.last_update)
+                >= Duration::from_millis(self.next_step_delay);
         }
 
         // Subsequent steps: check if they fit within frame budget
@@ -1172,19 +2275,16 @@ impl AnimationEngine {
     }
 
     fn execute_step(&mut self, step: AnimationStep) {
+        self.record_seek_snapshot();
         let step_clone = step.clone();
-        // Calculate delay for next step with randomization for typing steps
-        let mut rng = rand::rng();
+        // Keystroke timing is decided when the steps are generated
         self.next_step_delay = match &step {
-            AnimationStep::InsertChar { .. } | AnimationStep::TerminalTypeChar { .. } => {
-                // Add 70-130% variation to typing speed
-                let variation = rng.random_range(0.7..=1.3);
-                ((self.speed_ms as f64) * variation) as u64
-            }
-            AnimationStep::DialogTypeChar { .. } => {
-                // Dialog typing is slower (2x speed with variation)
-                let variation = rng.random_range(0.7..=1.3);
-                ((self.speed_ms as f64) * 2.0 * variation) as u64
+            AnimationStep::InsertChar { delay, .. }
+            | AnimationStep::DeleteChar { delay, .. }
+            | AnimationStep::DialogTypeChar { delay, .. }
+            | AnimationStep::TerminalTypeChar { delay, .. }
+            | AnimationStep::TerminalBackspace { delay } => {
+                self.scaled(self.speed_ms as f64 * delay)
             }
             AnimationStep::Pause { .. } => {
                 // Pause timing is driven by `pause_until`; don't add extra delay
@@ -1192,21 +2292,44 @@ impl AnimationEngine {
             }
             _ => {
                 // Other steps use base speed
-                self.speed_ms
+                self.scaled(self.speed_ms as f64)
             }
         };
+        // Fast-forwarding stops once the open file is done
+        if self.fast_forward
+            && matches!(
+                step,
+                AnimationStep::OpenFileDialogStart
+                    | AnimationStep::SwitchFile { .. }
+                    | AnimationStep::TerminalPrompt
+                    | AnimationStep::ResetState
+                    | AnimationStep::ShowSubmoduleCommit { .. }
+            )
+        {
+            self.fast_forward = false;
+        }
 
         match step {
-            AnimationStep::InsertChar { line, col, ch } => {
+            AnimationStep::InsertChar { line, col, ch, .. } => {
                 self.active_pane = ActivePane::Editor;
                 self.buffer.insert_char(line, col, ch);
                 self.buffer.cursor_line = line;
                 self.buffer.cursor_col = col + 1;
             }
-            AnimationStep::InsertLine { line, content } => {
+            AnimationStep::DeleteChar { line, col, .. } => {
+                self.active_pane = ActivePane::Editor;
+                self.buffer.delete_char(line, col);
+                self.buffer.cursor_line = line;
+                self.buffer.cursor_col = col;
+            }
+            AnimationStep::InsertLine {
+                line,
+                content,
+                line_ending,
+            } => {
                 self.active_pane = ActivePane::Editor;
                 let content_len = content.chars().count();
-                self.buffer.insert_line(line, content);
+                self.buffer.insert_line(line, content, line_ending);
                 self.buffer.cursor_line = line;
                 self.buffer.cursor_col = content_len;
 
@@ -1228,20 +2351,43 @@ impl AnimationEngine {
                 // Track line offset for old_highlights mapping
                 self.line_offset -= 1;
             }
+            AnimationStep::ShowCompletion {
+                line,
+                col,
+                candidates,
+                selected,
+            } => {
+                self.active_pane = ActivePane::Editor;
+                self.completion = Some(CompletionPopup {
+                    line,
+                    col,
+                    candidates,
+                    selected,
+                });
+            }
+            AnimationStep::AcceptCompletion { line, col, text } => {
+                self.active_pane = ActivePane::Editor;
+                self.completion = None;
+                for (i, ch) in text.chars().enumerate() {
+                    self.buffer.insert_char(line, col + i, ch);
+                }
+                self.buffer.cursor_line = line;
+                self.buffer.cursor_col = col + text.chars().count();
+            }
             AnimationStep::MoveCursor { line, col } => {
                 self.active_pane = ActivePane::Editor;
                 self.buffer.cursor_line = line;
                 self.buffer.cursor_col = col;
             }
             AnimationStep::Pause { multiplier } => {
-                let duration_ms = (self.speed_ms as f64 * multiplier) as u64;
-                self.pause_until = Some(Instant::now() + Duration::from_millis(duration_ms));
+                let duration_ms = self.scaled(self.speed_ms as f64 * multiplier);
+                self.pause_until = Some(self.clock.now() + Duration::from_millis(duration_ms));
             }
             AnimationStep::OpenFileDialogStart => {
                 self.dialog_typing_text = String::new();
                 self.dialog_title = Some("Open File...".to_string());
             }
-            AnimationStep::DialogTypeChar { ch } => {
+            AnimationStep::DialogTypeChar { ch, .. } => {
                 self.dialog_typing_text.push(ch);
             }
             AnimationStep::SwitchFile {
@@ -1249,22 +2395,26 @@ impl AnimationEngine {
                 old_content,
                 new_content,
                 path,
+                syntax_path,
             } => {
                 self.active_pane = ActivePane::Editor;
                 // Clear dialog when file is actually switched
                 self.dialog_title = None;
                 self.dialog_typing_text = String::new();
+                self.completion = None;
                 // Switch to new file
                 self.current_file_index = file_index;
                 self.current_file_path = Some(path.clone());
                 self.buffer = EditorBuffer::from_content(&old_content);
 
                 // Update typing speed based on file-specific rules
-                self.speed_ms = self.get_speed_for_file(&path);
+                self.speed_ms = self.speed_for_file(&path);
 
                 // Update syntax highlighter for new file
                 // This will clear language settings if not supported
-                self.highlighter.borrow_mut().set_language_from_path(&path);
+                self.highlighter
+                    .borrow_mut()
+                    .set_language_from_path(syntax_path.as_deref().unwrap_or(&path));
 
                 // Pre-calculate highlights for both old and new content
                 self.buffer.old_highlights = self.highlighter.borrow_mut().highlight(&old_content);
@@ -1297,13 +2447,19 @@ impl AnimationEngine {
                 // Start a new command line with prompt
                 self.terminal_lines.push("~ ".to_string());
             }
-            AnimationStep::TerminalTypeChar { ch } => {
+            AnimationStep::TerminalTypeChar { ch, .. } => {
                 self.active_pane = ActivePane::Terminal;
                 // Add character to the last terminal line
                 if let Some(last_line) = self.terminal_lines.last_mut() {
                     last_line.push(ch);
                 }
             }
+            AnimationStep::TerminalBackspace { .. } => {
+                self.active_pane = ActivePane::Terminal;
+                if let Some(last_line) = self.terminal_lines.last_mut() {
+                    last_line.pop();
+                }
+            }
             AnimationStep::TerminalOutput { text } => {
                 self.active_pane = ActivePane::Terminal;
                 // Add output line
@@ -1319,6 +2475,16 @@ impl AnimationEngine {
                 self.buffer = EditorBuffer::new();
                 self.current_file_path = None;
                 self.active_pane = ActivePane::Terminal;
+                self.submodule_commit = None;
+                self.completion = None;
+            }
+            AnimationStep::ShowSubmoduleCommit { index } => {
+                self.submodule_commit = index;
+                self.completion = None;
+                self.current_file_index = 0;
+                self.buffer = EditorBuffer::new();
+                self.current_file_path = None;
+                self.line_offset = 0;
             }
         }
 
@@ -1328,7 +2494,8 @@ impl AnimationEngine {
         self.update_scroll();
     }
 
-    fn calculate_line_display_height(&self, line: &str) -> usize {
+    /// Returns the number of editor rows `line` takes when wrapped.
+    pub fn calculate_line_display_height(&self, line: &str) -> usize {
         if self.content_width == 0 {
             return 1;
         }
@@ -1400,3 +2567,341 @@ impl AnimationEngine {
         self.state == AnimationState::Finished
     }
 }
+
+// Returns true if a hunk adds or removes a declaration or an import
+fn is_signature_hunk(hunk: &DiffHunk) -> bool {
+    hunk.lines
+        .iter()
+        .filter(|line| line.change_type != LineChangeType::Context)
+        .any(|line| {
+            let first_word = line
+                .content
+                .trim_start()
+                .split(|c: char| c.is_whitespace() || c == '(')
+                .next()
+                .unwrap_or_default();
+            SIGNATURE_KEYWORDS.contains(&first_word)
+        })
+}
+
+/// Applies `hunks`, in file order, to `old_content`.
+pub(crate) fn apply_hunks(old_content: &str, hunks: &[&DiffHunk]) -> String {
+    let old_lines: Vec<&str> = old_content.split_inclusive('\n').collect();
+    let mut content = String::with_capacity(old_content.len());
+    let mut old_line = 0;
+    for hunk in hunks {
+        // A hunk that only adds lines starts after `old_start`
+        let hunk_start = if hunk.old_lines == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        while old_line < hunk_start.min(old_lines.len()) {
+            content.push_str(old_lines[old_line]);
+            old_line += 1;
+        }
+        for line in &hunk.lines {
+            match line.change_type {
+                LineChangeType::Context => {
+                    if let Some(old) = old_lines.get(old_line) {
+                        content.push_str(old);
+                    }
+                    old_line += 1;
+                }
+                LineChangeType::Deletion => old_line += 1,
+                LineChangeType::Addition => {
+                    content.push_str(&line.content);
+                    content.push_str(line.line_ending.as_str());
+                }
+            }
+        }
+    }
+    for old in old_lines.iter().skip(old_line) {
+        content.push_str(old);
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::git::{GitRepository, LineChange};
+    use crate::test_support::{commit_metadata, line, TestRepo};
+
+    // Simulated time it takes to play a commit on an engine set up by
+    // `configure`, in milliseconds
+    fn play(seed: u64, configure: impl FnOnce(&mut AnimationEngine)) -> u64 {
+        let metadata = commit_metadata("Add feature", Vec::new());
+        let clock = ManualClock::new();
+        let mut engine = AnimationEngine::new(30);
+        engine.set_clock(Box::new(clock.clone()));
+        engine.set_rng(StdRng::seed_from_u64(seed));
+        engine.set_typing_settings(&Default::default());
+        configure(&mut engine);
+        engine.load_commit(&metadata);
+
+        // Nothing happens until time moves
+        assert!(!engine.tick());
+
+        while !engine.is_finished() {
+            clock.advance(Duration::from_millis(1));
+            engine.tick();
+        }
+        clock.elapsed().as_millis() as u64
+    }
+
+    #[test]
+    fn test_engine_runs_on_simulated_time() {
+        let elapsed = play(3, |_| {});
+        assert!(elapsed > 1000);
+        assert_eq!(play(3, |_| {}), elapsed);
+    }
+
+    #[test]
+    fn test_playback_rate_scales_simulated_time() {
+        let normal = play(3, |_| {}) as f64;
+        let demo = play(3, |engine| engine.set_playback_rate(2.0)) as f64;
+        assert!((0.4..0.6).contains(&(demo / normal)), "{demo} / {normal}");
+    }
+
+    #[test]
+    fn test_commit_duration() {
+        let fit = |duration: &str| {
+            let duration: CommitDuration = duration.parse().unwrap();
+            play(3, |engine| engine.set_commit_duration(Some(duration)))
+        };
+        let elapsed = fit("10s");
+        assert!((9_000..11_000).contains(&elapsed), "{elapsed}");
+        let elapsed = fit("1m..2m");
+        assert!((59_000..61_000).contains(&elapsed), "{elapsed}");
+        // Commits within the bounds play as they are
+        assert_eq!(fit("..1h"), play(3, |_| {}));
+
+        let bounds: CommitDuration = "1m30s..".parse().unwrap();
+        assert_eq!(bounds.min, Some(Duration::from_secs(90)));
+        assert_eq!(bounds.max, None);
+        assert!("2m..1m".parse::<CommitDuration>().is_err());
+        assert!("45x".parse::<CommitDuration>().is_err());
+        assert!("..".parse::<CommitDuration>().is_err());
+        assert!("9999999999999999h".parse::<CommitDuration>().is_err());
+        assert!("18446744073709551615s1s".parse::<CommitDuration>().is_err());
+    }
+
+    #[test]
+    fn test_loop_repeats_steps() {
+        let clock = ManualClock::new();
+        let mut engine = AnimationEngine::new(30);
+        engine.set_clock(Box::new(clock.clone()));
+        engine.load_commit(&commit_metadata("Add feature", Vec::new()));
+        engine.set_loop(Some(5..15));
+
+        let mut restarts = 0;
+        let mut previous = 0;
+        for _ in 0..20_000 {
+            clock.advance(Duration::from_millis(1));
+            engine.tick();
+            let (step, _) = engine.progress();
+            assert!(step < 15, "{step}");
+            if step < previous {
+                assert_eq!(step, 5);
+                restarts += 1;
+            }
+            previous = step;
+        }
+        assert!(restarts > 1);
+        assert!(!engine.is_finished());
+    }
+
+    // Commit adding two 40-line files, long enough to span well over a
+    // thousand steps
+    fn two_file_commit(test_repo: &TestRepo) -> CommitMetadata {
+        let content = |name: &str| {
+            (0..40)
+                .map(|i| format!("fn {}_{}() {{}}\n", name, i))
+                .collect::<String>()
+        };
+        let first = test_repo.commit_file("a.rs", content("a"));
+        let second = test_repo.commit_file("b.rs", content("b"));
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let mut metadata = repo.get_commit(&second.to_string()).unwrap();
+        metadata
+            .changes
+            .extend(repo.get_commit(&first.to_string()).unwrap().changes);
+        metadata
+    }
+
+    fn load(metadata: &CommitMetadata) -> AnimationEngine {
+        let mut engine = AnimationEngine::new(30);
+        engine.set_rng(StdRng::seed_from_u64(3));
+        engine.set_typing_settings(&TypingSettings::default());
+        engine.load_commit(metadata);
+        engine
+    }
+
+    // Everything seeking has to restore
+    fn view(engine: &AnimationEngine) -> String {
+        format!(
+            "{:?} {} {} {} {:?} {:?}",
+            engine.buffer.lines,
+            engine.buffer.cursor_line,
+            engine.buffer.cursor_col,
+            engine.line_offset,
+            engine.current_file_path,
+            engine.current_metadata().map(|meta| &meta.hash),
+        )
+    }
+
+    // View after each step of playing `metadata` from the start
+    fn sequential_views(metadata: &CommitMetadata) -> Vec<String> {
+        let mut engine = load(metadata);
+        let mut views = vec![view(&engine)];
+        while engine.advance().is_some() {
+            views.push(view(&engine));
+        }
+        views
+    }
+
+    #[test]
+    fn test_seek_matches_sequential_playback() {
+        let test_repo = TestRepo::new();
+        let metadata = two_file_commit(&test_repo);
+        let views = sequential_views(&metadata);
+        let total = views.len() - 1;
+        assert!(total > 1000);
+
+        let mut engine = load(&metadata);
+        for target in [1200, 10, total, 0, 999, 1001, total / 2] {
+            engine.seek(target);
+            assert_eq!(engine.progress(), (target, total));
+            assert_eq!(view(&engine), views[target], "seek to {}", target);
+        }
+
+        engine.seek(0);
+        assert!(engine.seek_file(true));
+        assert!(engine.seek_file(true));
+        assert_eq!(engine.current_file_path.as_deref(), Some("b.rs"));
+        assert!(!engine.seek_file(true));
+        assert!(engine.seek_file(false));
+        assert_eq!(engine.current_file_path.as_deref(), Some("a.rs"));
+    }
+
+    #[test]
+    fn test_rewind_matches_sequential_playback() {
+        let test_repo = TestRepo::new();
+        let metadata = two_file_commit(&test_repo);
+        let views = sequential_views(&metadata);
+        let total = views.len() - 1;
+
+        // Stepping back from the end rewinds all the way to the start, after
+        // the checkout intro
+        let mut engine = load(&metadata);
+        let start = engine
+            .steps()
+            .iter()
+            .position(|step| matches!(step, AnimationStep::ResetState))
+            .unwrap()
+            + 1;
+        engine.seek(total);
+        assert!(engine.restore_line_checkpoint());
+        let (mut previous, _) = engine.progress();
+        assert_eq!(view(&engine), views[previous]);
+        while engine.restore_change_checkpoint() {
+            let (current, _) = engine.progress();
+            assert!(current < previous);
+            assert_eq!(view(&engine), views[current]);
+            previous = current;
+        }
+        assert_eq!(engine.progress(), (start, total));
+    }
+
+    #[test]
+    fn test_anchor_survives_regenerated_steps() {
+        let test_repo = TestRepo::new();
+        let content = |changed: &[usize]| {
+            (0..40)
+                .map(|i| {
+                    if changed.contains(&i) {
+                        format!("let value_{} = compute({});\n", i, i)
+                    } else {
+                        format!("fn line_{}() {{}}\n", i)
+                    }
+                })
+                .collect::<String>()
+        };
+        test_repo.commit_file("a.rs", content(&[]));
+        let oid = test_repo.commit_file("a.rs", content(&[5, 20, 35]));
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+
+        let load = |seed| {
+            let mut engine = AnimationEngine::new(30);
+            engine.set_rng(StdRng::seed_from_u64(seed));
+            engine.set_typing_settings(&TypingSettings {
+                typo_rate: 0.3,
+                ..Default::default()
+            });
+            engine.set_hunk_order(HunkOrder::Random);
+            engine.load_commit(&metadata);
+            engine
+        };
+        let first = load(1);
+        let second = load(2);
+        assert_ne!(first.progress().1, second.progress().1);
+
+        let total = first.progress().1;
+        assert_eq!(first.anchor(0), None);
+        let mut lines = 0;
+        for step in 0..=total {
+            let Some(anchor) = first.anchor(step) else {
+                continue;
+            };
+            let target = second.anchor_step(&anchor).unwrap();
+            if anchor.offset == 0 {
+                assert_eq!(second.anchor(target), Some(anchor), "step {}", step);
+                lines += usize::from(anchor.line.is_some());
+            }
+        }
+        assert!(lines > 6);
+    }
+
+    fn hunk(old_start: usize, old_lines: usize, lines: Vec<LineChange>) -> DiffHunk {
+        DiffHunk {
+            old_start,
+            old_lines,
+            new_start: 0,
+            new_lines: 0,
+            lines,
+        }
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        use LineChangeType::*;
+        let old = "a\nb\nc\nd\ne\n";
+        let replace = hunk(
+            1,
+            3,
+            vec![
+                line(Context, "a"),
+                line(Deletion, "b"),
+                line(Addition, "B"),
+                line(Context, "c"),
+            ],
+        );
+        let insert = hunk(4, 0, vec![line(Addition, "x")]);
+        let delete = hunk(5, 1, vec![line(Deletion, "e")]);
+
+        assert_eq!(apply_hunks(old, &[]), old);
+        assert_eq!(
+            apply_hunks(old, &[&replace, &insert, &delete]),
+            "a\nB\nc\nd\nx\n"
+        );
+        assert_eq!(apply_hunks(old, &[&insert, &delete]), "a\nb\nc\nd\nx\n");
+        assert_eq!(apply_hunks(old, &[&replace]), "a\nB\nc\nd\ne\n");
+        assert_eq!(
+            apply_hunks(old, &[&hunk(0, 0, vec![line(Addition, "top")])]),
+            "top\na\nb\nc\nd\ne\n"
+        );
+    }
+}
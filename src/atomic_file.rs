@@ -0,0 +1,23 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Replaces the contents of `path`, creating its directory if needed. The
+/// contents go to a temporary file next to it first, so an interrupted write
+/// leaves the old file in place rather than a truncated one.
+pub fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    if let Err(error) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error).with_context(|| format!("Failed to replace {}", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::animation::EditAnchor;
+use crate::atomic_file;
+use crate::state::StateStore;
+
+/// A named position in the playback of a commit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    /// Hash of the commit
+    pub commit: String,
+    /// Number of animation steps played when the bookmark was taken, used
+    /// when it has no anchor
+    pub step: usize,
+    /// Position in the edits of the commit, which unlike `step` doesn't
+    /// depend on typos or hunk order
+    #[serde(default)]
+    pub anchor: Option<EditAnchor>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BookmarkFile {
+    #[serde(default, rename = "bookmark")]
+    bookmarks: Vec<Bookmark>,
+}
+
+/// Bookmarks stored in a TOML file, in the order they were added
+#[derive(Debug, Clone)]
+pub struct Bookmarks {
+    path: PathBuf,
+    items: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Opens the bookmarks at `~/.local/state/gitlogue/bookmarks.toml`.
+    pub fn open_default() -> Result<Self> {
+        Self::open(StateStore::state_dir()?.join("bookmarks.toml"))
+    }
+
+    /// Opens the bookmarks stored in `path`, which is created on the first
+    /// change if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let items = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read bookmarks file: {}", path.display()))?;
+            toml::from_str::<BookmarkFile>(&contents)
+                .with_context(|| format!("Failed to parse bookmarks file: {}", path.display()))?
+                .bookmarks
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { path, items })
+    }
+
+    pub fn items(&self) -> &[Bookmark] {
+        &self.items
+    }
+
+    /// Adds `bookmark`, replacing any bookmark with the same name, and saves
+    /// the file.
+    pub fn add(&mut self, bookmark: Bookmark) -> Result<()> {
+        match self.items.iter_mut().find(|b| b.name == bookmark.name) {
+            Some(existing) => *existing = bookmark,
+            None => self.items.push(bookmark),
+        }
+        self.save()
+    }
+
+    /// Removes the bookmark at `index` and saves the file.
+    pub fn remove(&mut self, index: usize) -> Result<()> {
+        if index < self.items.len() {
+            self.items.remove(index);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = BookmarkFile {
+            bookmarks: self.items.clone(),
+        };
+        let contents = toml::to_string(&file).context("Failed to serialize bookmarks")?;
+        atomic_file::write(&self.path, &contents)
+            .with_context(|| format!("Failed to save bookmarks file: {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("gitlogue-bookmarks-{}", std::process::id()))
+            .join("bookmarks.toml");
+        let bookmark = |name: &str, step| Bookmark {
+            name: name.to_string(),
+            commit: "0123456789abcdef".to_string(),
+            step,
+            anchor: (step > 100).then_some(EditAnchor {
+                file: 1,
+                hunk: Some(2),
+                line: Some(3),
+                offset: 4,
+            }),
+        };
+
+        let mut bookmarks = Bookmarks::open(&path).unwrap();
+        assert!(bookmarks.items().is_empty());
+        bookmarks.add(bookmark("intro", 10)).unwrap();
+        bookmarks.add(bookmark("fix", 250)).unwrap();
+        bookmarks.add(bookmark("intro", 42)).unwrap();
+
+        let mut reopened = Bookmarks::open(&path).unwrap();
+        assert_eq!(
+            reopened.items(),
+            &[bookmark("intro", 42), bookmark("fix", 250)]
+        );
+        reopened.remove(0).unwrap();
+        assert_eq!(
+            Bookmarks::open(&path).unwrap().items(),
+            &[bookmark("fix", 250)]
+        );
+        // Only the bookmarks file is left behind
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source of the current time for the animation engine
+pub trait Clock: Send {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock time, for playing in real time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Time that only moves when advanced, for driving the engine faster than
+/// real time. Clones share the same time, so one clone can be handed to the
+/// engine while another advances it.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Moves time forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// Returns the time advanced since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+        let before = clock.now();
+
+        handle.advance(Duration::from_millis(250));
+        assert_eq!(clock.now() - before, Duration::from_millis(250));
+        assert_eq!(clock.elapsed(), Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::syntax::Highlighter;
+
+/// Characters typed before the completion popup opens
+const COMPLETION_PREFIX_LEN: usize = 3;
+/// Shortest identifier worth completing
+const MIN_COMPLETION_LEN: usize = 6;
+/// Candidates shown in the popup at once
+const MAX_CANDIDATES: usize = 5;
+
+/// Part of a line as it is entered in the editor
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chunk {
+    /// Typed key by key
+    Type(String),
+    /// Rest of an identifier, inserted at once by accepting `candidates[selected]`
+    Complete {
+        text: String,
+        candidates: Vec<String>,
+        selected: usize,
+    },
+}
+
+/// Simulates IDE autocomplete: identifiers that are already in the buffer
+/// are completed after their first few characters instead of being typed out
+pub struct Completer {
+    /// Identifiers of either version of the file; other words, such as
+    /// keywords and prose in comments, are always typed out
+    identifiers: HashSet<String>,
+    /// Identifiers already in the buffer, offered as candidates
+    known: BTreeSet<String>,
+}
+
+impl Completer {
+    /// Collects identifiers of both versions of the file at `syntax_path`
+    /// with `highlighter`, which is switched to its language; files without
+    /// a tree-sitter grammar get no completions.
+    pub fn new(
+        highlighter: &mut Highlighter,
+        syntax_path: &str,
+        old_content: &str,
+        new_content: &str,
+    ) -> Self {
+        if !highlighter.set_language_from_path(syntax_path) {
+            return Self {
+                identifiers: HashSet::new(),
+                known: BTreeSet::new(),
+            };
+        }
+
+        let known: BTreeSet<String> = highlighter.identifiers(old_content).into_iter().collect();
+        let mut identifiers: HashSet<String> =
+            highlighter.identifiers(new_content).into_iter().collect();
+        identifiers.extend(known.iter().cloned());
+        Self { identifiers, known }
+    }
+
+    /// Splits a line into typed and completed chunks. Identifiers typed on
+    /// the line become candidates for the lines after it.
+    pub fn chunks(&mut self, text: &str) -> Vec<Chunk> {
+        let mut chunks = Vec::new();
+        let mut typed_from = 0;
+        let mut words = Vec::new();
+
+        for (start, end) in word_bounds(text) {
+            let word = &text[start..end];
+            if !self.identifiers.contains(word) {
+                continue;
+            }
+            words.push(word);
+
+            if word.chars().count() < MIN_COMPLETION_LEN || !self.known.contains(word) {
+                continue;
+            }
+            let prefix_end = start
+                + word
+                    .char_indices()
+                    .nth(COMPLETION_PREFIX_LEN)
+                    .map_or(word.len(), |(i, _)| i);
+            let (candidates, selected) = self.candidates(&text[start..prefix_end], word);
+
+            chunks.push(Chunk::Type(text[typed_from..prefix_end].to_string()));
+            chunks.push(Chunk::Complete {
+                text: text[prefix_end..end].to_string(),
+                candidates,
+                selected,
+            });
+            typed_from = end;
+        }
+
+        if typed_from < text.len() {
+            chunks.push(Chunk::Type(text[typed_from..].to_string()));
+        }
+        for word in words {
+            self.known.insert(word.to_string());
+        }
+        chunks
+    }
+
+    // Known identifiers starting with `prefix`, sorted, in a window that
+    // includes `word`
+    fn candidates(&self, prefix: &str, word: &str) -> (Vec<String>, usize) {
+        let matching: Vec<&String> = self
+            .known
+            .range(prefix.to_string()..)
+            .take_while(|candidate| candidate.starts_with(prefix))
+            .collect();
+        let index = matching.iter().position(|c| *c == word).unwrap_or(0);
+        let first = index.saturating_sub(MAX_CANDIDATES - 1);
+        let candidates = matching
+            .iter()
+            .skip(first)
+            .take(MAX_CANDIDATES)
+            .map(|c| c.to_string())
+            .collect();
+        (candidates, index - first)
+    }
+}
+
+// Byte ranges of the words (letters, digits and underscores) of `text`
+fn word_bounds(text: &str) -> Vec<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut bounds = Vec::new();
+    let mut start = None;
+    for (i, ch) in text.char_indices() {
+        match (start, is_word(ch)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                bounds.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        bounds.push((s, text.len()));
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_identifiers_are_completed() {
+        let old = "fn render_frame() {}\nfn render_footer() {}\n";
+        let new = "fn render_frame() {}\nfn render_footer() {}\nfn draw() { render_frame(); }\n";
+        let mut completer = Completer::new(&mut Highlighter::new(), "main.rs", old, new);
+
+        let chunks = completer.chunks("fn draw() { render_frame(); }");
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk::Type("fn draw() { ren".to_string()),
+                Chunk::Complete {
+                    text: "der_frame".to_string(),
+                    candidates: vec!["render_footer".to_string(), "render_frame".to_string()],
+                    selected: 1,
+                },
+                Chunk::Type("(); }".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_identifiers_become_candidates() {
+        let new = "let counter_value = 1;\nlet total = counter_value + 1;\n";
+        let mut completer = Completer::new(&mut Highlighter::new(), "main.rs", "", new);
+
+        // The first use is typed out, later uses are completed
+        assert_eq!(
+            completer.chunks("let counter_value = 1;"),
+            vec![Chunk::Type("let counter_value = 1;".to_string())]
+        );
+        let chunks = completer.chunks("let total = counter_value + 1;");
+        assert_eq!(chunks[0], Chunk::Type("let total = cou".to_string()));
+        assert_eq!(chunks[2], Chunk::Type(" + 1;".to_string()));
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let mut completer = Completer::new(
+            &mut Highlighter::new(),
+            "notes.unknownext",
+            "",
+            "some_identifier",
+        );
+        assert_eq!(
+            completer.chunks("some_identifier"),
+            vec![Chunk::Type("some_identifier".to_string())]
+        );
+    }
+}
@@ -19,6 +19,38 @@ pub struct Config {
     pub ignore_patterns: Vec<String>,
     #[serde(default)]
     pub speed_rules: Vec<String>,
+    #[serde(default = "default_diff_algorithm")]
+    pub diff_algorithm: String,
+    #[serde(default = "default_context_lines")]
+    pub context_lines: u32,
+    #[serde(default)]
+    pub ignore_whitespace: bool,
+    #[serde(default)]
+    pub ignore_blank_lines: bool,
+    #[serde(default = "default_whitespace_commits")]
+    pub whitespace_commits: String,
+    #[serde(default = "default_image_protocol")]
+    pub image_protocol: String,
+    #[serde(default)]
+    pub submodule_replay: bool,
+    #[serde(default = "default_typing")]
+    pub typing: String,
+    #[serde(default = "default_typo_rate")]
+    pub typo_rate: f64,
+    #[serde(default = "default_punctuation_pause")]
+    pub punctuation_pause: f64,
+    #[serde(default = "default_brace_pause")]
+    pub brace_pause: f64,
+    #[serde(default = "default_autocomplete")]
+    pub autocomplete: bool,
+    #[serde(default = "default_hunk_order")]
+    pub hunk_order: String,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_speed_preset")]
+    pub speed_preset: String,
+    #[serde(default)]
+    pub commit_duration: Option<String>,
 }
 
 fn default_theme() -> String {
@@ -45,6 +77,50 @@ fn default_ignore_patterns() -> Vec<String> {
     Vec::new()
 }
 
+fn default_diff_algorithm() -> String {
+    "myers".to_string()
+}
+
+fn default_context_lines() -> u32 {
+    3
+}
+
+fn default_whitespace_commits() -> String {
+    "show".to_string()
+}
+
+fn default_image_protocol() -> String {
+    "auto".to_string()
+}
+
+fn default_typing() -> String {
+    "natural".to_string()
+}
+
+fn default_typo_rate() -> f64 {
+    0.02
+}
+
+fn default_punctuation_pause() -> f64 {
+    3.0
+}
+
+fn default_brace_pause() -> f64 {
+    5.0
+}
+
+fn default_autocomplete() -> bool {
+    true
+}
+
+fn default_hunk_order() -> String {
+    "top-down".to_string()
+}
+
+fn default_speed_preset() -> String {
+    "normal".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -55,6 +131,22 @@ impl Default for Config {
             loop_playback: default_loop(),
             ignore_patterns: default_ignore_patterns(),
             speed_rules: Vec::new(),
+            diff_algorithm: default_diff_algorithm(),
+            context_lines: default_context_lines(),
+            ignore_whitespace: false,
+            ignore_blank_lines: false,
+            whitespace_commits: default_whitespace_commits(),
+            image_protocol: default_image_protocol(),
+            submodule_replay: false,
+            typing: default_typing(),
+            typo_rate: default_typo_rate(),
+            punctuation_pause: default_punctuation_pause(),
+            brace_pause: default_brace_pause(),
+            autocomplete: default_autocomplete(),
+            hunk_order: default_hunk_order(),
+            seed: None,
+            speed_preset: default_speed_preset(),
+            commit_duration: None,
         }
     }
 }
@@ -109,6 +201,33 @@ impl Config {
             }
             doc["speed_rules"] = toml_edit::value(speed_array);
 
+            doc["diff_algorithm"] = toml_edit::value(self.diff_algorithm.as_str());
+            doc["context_lines"] = toml_edit::value(self.context_lines as i64);
+            doc["ignore_whitespace"] = toml_edit::value(self.ignore_whitespace);
+            doc["ignore_blank_lines"] = toml_edit::value(self.ignore_blank_lines);
+            doc["whitespace_commits"] = toml_edit::value(self.whitespace_commits.as_str());
+            doc["image_protocol"] = toml_edit::value(self.image_protocol.as_str());
+            doc["submodule_replay"] = toml_edit::value(self.submodule_replay);
+            doc["typing"] = toml_edit::value(self.typing.as_str());
+            doc["typo_rate"] = toml_edit::value(self.typo_rate);
+            doc["punctuation_pause"] = toml_edit::value(self.punctuation_pause);
+            doc["brace_pause"] = toml_edit::value(self.brace_pause);
+            doc["autocomplete"] = toml_edit::value(self.autocomplete);
+            doc["hunk_order"] = toml_edit::value(self.hunk_order.as_str());
+            match self.seed {
+                Some(seed) => doc["seed"] = toml_edit::value(seed as i64),
+                None => {
+                    doc.remove("seed");
+                }
+            }
+            doc["speed_preset"] = toml_edit::value(self.speed_preset.as_str());
+            match &self.commit_duration {
+                Some(duration) => doc["commit_duration"] = toml_edit::value(duration.as_str()),
+                None => {
+                    doc.remove("commit_duration");
+                }
+            }
+
             doc.to_string()
         } else {
             // Create new config with comments
@@ -147,7 +266,7 @@ impl Config {
                  # Show background colors (set to false for transparent background)\n\
                  background = {}\n\
                  \n\
-                 # Commit playback order: random, asc, or desc\n\
+                 # Commit playback order: random, shuffle, asc, or desc\n\
                  order = \"{}\"\n\
                  \n\
                  # Loop the animation continuously\n\
@@ -159,14 +278,79 @@ impl Config {
                  \n\
                  # Speed rules for different file types (pattern:milliseconds)\n\
                  # Examples: [\"*.java:50\", \"*.xml:5\", \"*.rs:30\"]\n\
-                 speed_rules = {}\n",
+                 speed_rules = {}\n\
+                 \n\
+                 # Diff algorithm: myers, minimal, patience, or histogram\n\
+                 diff_algorithm = \"{}\"\n\
+                 \n\
+                 # Unchanged lines shown around each change\n\
+                 context_lines = {}\n\
+                 \n\
+                 # Ignore whitespace and blank line changes when diffing\n\
+                 ignore_whitespace = {}\n\
+                 ignore_blank_lines = {}\n\
+                 \n\
+                 # Whitespace-only commits: show, compact, or skip\n\
+                 whitespace_commits = \"{}\"\n\
+                 \n\
+                 # Image previews: auto, halfblocks, kitty, or sixel\n\
+                 image_protocol = \"{}\"\n\
+                 \n\
+                 # Replay the new commits of a checked-out submodule when its pointer changes\n\
+                 submodule_replay = {}\n\
+                 \n\
+                 # Typing style: natural (typos, rhythm and pauses) or uniform\n\
+                 typing = \"{}\"\n\
+                 \n\
+                 # Natural typing: chance of a typo per letter, and delay multipliers\n\
+                 # after punctuation or line ends and before opening braces\n\
+                 typo_rate = {}\n\
+                 punctuation_pause = {}\n\
+                 brace_pause = {}\n\
+                 \n\
+                 # Complete identifiers already in the file after a few characters\n\
+                 autocomplete = {}\n\
+                 \n\
+                 # Hunk order: top-down, bottom-up, signature-first, random, or interleave\n\
+                 hunk_order = \"{}\"\n\
+                 \n\
+                 # Seed for commit selection and typing variation, for reproducible playback\n\
+                 {}seed = {}\n\
+                 \n\
+                 # Playback rate: relaxed, normal, demo, or turbo\n\
+                 speed_preset = \"{}\"\n\
+                 \n\
+                 # Fit each commit into a duration (\"45s\") or between bounds (\"30s..2m\")\n\
+                 {}commit_duration = \"{}\"\n",
                 self.theme,
                 self.speed,
                 self.background,
                 self.order,
                 self.loop_playback,
                 patterns_str,
-                speed_rules_str
+                speed_rules_str,
+                self.diff_algorithm,
+                self.context_lines,
+                self.ignore_whitespace,
+                self.ignore_blank_lines,
+                self.whitespace_commits,
+                self.image_protocol,
+                self.submodule_replay,
+                self.typing,
+                self.typo_rate,
+                self.punctuation_pause,
+                self.brace_pause,
+                self.autocomplete,
+                self.hunk_order,
+                if self.seed.is_some() { "" } else { "# " },
+                self.seed.unwrap_or(42),
+                self.speed_preset,
+                if self.commit_duration.is_some() {
+                    ""
+                } else {
+                    "# "
+                },
+                self.commit_duration.as_deref().unwrap_or("45s")
             )
         };
 
@@ -0,0 +1,56 @@
+use encoding_rs::{Encoding, UTF_8};
+
+/// Determine the text encoding of file content.
+///
+/// A declared encoding (e.g. from the `working-tree-encoding` attribute) wins,
+/// then a byte order mark, then valid UTF-8. Anything else is guessed from the
+/// byte distribution, which covers legacy encodings like Latin-1 and Shift-JIS.
+pub fn detect(bytes: &[u8], declared: Option<&str>) -> &'static Encoding {
+    if let Some(encoding) = declared.and_then(|label| Encoding::for_label(label.as_bytes())) {
+        return encoding;
+    }
+
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, false)
+}
+
+/// Decode bytes with the given encoding.
+///
+/// A byte order mark is kept as a character so that whole-file content and
+/// individually decoded diff lines stay consistent with each other.
+pub fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_utf8() {
+        assert_eq!(detect("héllo\n".as_bytes(), None), UTF_8);
+    }
+
+    #[test]
+    fn test_detect_latin1() {
+        let bytes = b"caf\xe9 cr\xe8me br\xfbl\xe9e\n";
+        let encoding = detect(bytes, None);
+        assert_eq!(decode(bytes, encoding), "café crème brûlée\n");
+    }
+
+    #[test]
+    fn test_declared_encoding_wins() {
+        let bytes = b"h\x00i\x00\n\x00";
+        let encoding = detect(bytes, Some("UTF-16LE"));
+        assert_eq!(decode(bytes, encoding), "hi\n");
+    }
+}
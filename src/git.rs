@@ -1,13 +1,24 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local, Utc};
 use chrono_english::{parse_date_string, Dialect};
-use git2::{Commit as Git2Commit, Delta, DiffOptions, Oid, Repository};
+use clap::ValueEnum;
+use encoding_rs::{Encoding, UTF_8};
+use git2::{AttrCheckFlags, Commit as Git2Commit, Delta, DiffOptions, Oid, Repository};
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use rand::RngExt;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
 use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
 use std::sync::OnceLock;
 
+use crate::encoding;
+use crate::lockfile::{self, DependencyChange};
+use crate::preview::{self, BinaryChange};
+use crate::state::{PlaybackCursor, StateStore};
+use crate::transform;
+
 // Thread-safe global pattern matcher for user-defined ignore patterns
 static USER_PATTERNS: OnceLock<GlobSet> = OnceLock::new();
 
@@ -18,6 +29,105 @@ const MAX_BLOB_SIZE: usize = 500 * 1024;
 // Files with more changes will be skipped to prevent performance issues
 const MAX_CHANGE_LINES: usize = 2000;
 
+// Maximum size (20MB) of lock files and notebooks, which are parsed rather than typed out
+const MAX_PARSED_FILE_SIZE: usize = 20 * 1024 * 1024;
+
+// Maximum number of submodule commits listed, and replayed when enabled, per pointer change
+const MAX_SUBMODULE_COMMITS: usize = 20;
+const MAX_SUBMODULE_REPLAYS: usize = 5;
+
+// Maximum number of recently shown commits to remember per repository
+const MAX_RECENT_COMMITS: usize = 1000;
+
+/// Direction of sequential playback, used to key saved resume cursors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackDirection {
+    Asc,
+    Desc,
+}
+
+impl PlaybackDirection {
+    fn as_str(&self) -> &str {
+        match self {
+            PlaybackDirection::Asc => "asc",
+            PlaybackDirection::Desc => "desc",
+        }
+    }
+}
+
+/// Outcome of positioning playback at a previously saved cursor
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResumeOutcome {
+    /// Nothing saved for this repository, order and filter combination
+    NoCursor,
+    /// Continuing with the commit after the saved one
+    Resumed,
+    /// The saved commit is no longer in history; continuing from its author date
+    Rewritten,
+    /// The saved commit was the last one; starting over from the beginning
+    Restarted,
+}
+
+/// Algorithm used to compute line diffs
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum DiffAlgorithm {
+    #[default]
+    Myers,
+    Minimal,
+    Patience,
+    /// libgit2 has no histogram diff; this uses patience, which histogram extends
+    Histogram,
+}
+
+/// How commits that only change whitespace are played
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum WhitespaceCommits {
+    /// Type them out like any other commit
+    #[default]
+    Show,
+    /// List the reformatted files instead of typing them
+    Compact,
+    /// Leave them out of playback
+    Skip,
+}
+
+/// Options used when computing commit and working tree diffs
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffSettings {
+    pub algorithm: DiffAlgorithm,
+    pub context_lines: u32,
+    pub ignore_whitespace: bool,
+    pub ignore_blank_lines: bool,
+    pub whitespace_commits: WhitespaceCommits,
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: DiffAlgorithm::default(),
+            context_lines: 3,
+            ignore_whitespace: false,
+            ignore_blank_lines: false,
+            whitespace_commits: WhitespaceCommits::default(),
+        }
+    }
+}
+
+impl DiffSettings {
+    fn diff_options(&self) -> DiffOptions {
+        let mut opts = DiffOptions::new();
+        opts.context_lines(self.context_lines)
+            .ignore_whitespace(self.ignore_whitespace)
+            .ignore_blank_lines(self.ignore_blank_lines)
+            .minimal(self.algorithm == DiffAlgorithm::Minimal)
+            .patience(matches!(
+                self.algorithm,
+                DiffAlgorithm::Patience | DiffAlgorithm::Histogram
+            ));
+        opts
+    }
+}
+
 /// Specifies which working tree changes to show in diff mode
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub enum DiffMode {
@@ -149,6 +259,21 @@ pub fn parse_date(input: &str) -> Result<DateTime<Utc>> {
         .with_context(|| format!("Invalid date format: '{}'. Use formats like '2024-01-01', '1 week ago', 'yesterday'", input))
 }
 
+// A date filter as given on the command line and the date it resolved to
+struct DateFilter {
+    spec: String,
+    date: DateTime<Utc>,
+}
+
+impl DateFilter {
+    fn parse(spec: &str) -> Result<Self> {
+        Ok(Self {
+            spec: spec.to_string(),
+            date: parse_date(spec)?,
+        })
+    }
+}
+
 // Check if a commit date is within the specified date range
 fn matches_date_filter(
     commit: &Git2Commit,
@@ -173,6 +298,63 @@ fn matches_date_filter(
     Ok(true)
 }
 
+/// File content decoded to text, together with the encoding it was decoded from
+type DecodedText = (String, &'static Encoding);
+
+/// Hunks and contents of a file diffed through a content transformer
+struct TransformedView {
+    pub(crate) old_content: Option<String>,
+    pub(crate) new_content: Option<String>,
+    pub(crate) hunks: Vec<DiffHunk>,
+    pub(crate) diff: String,
+    pub(crate) syntax_path: Option<String>,
+}
+
+// Value of the `working-tree-encoding` attribute for a path, if set
+fn working_tree_encoding<'r>(repo: &'r Repository, path: &Path) -> Option<&'r str> {
+    repo.get_attr(
+        path,
+        "working-tree-encoding",
+        AttrCheckFlags::FILE_THEN_INDEX,
+    )
+    .ok()
+    .flatten()
+}
+
+// Same heuristic as git: a NUL byte in the first 8000 bytes means binary
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|&b| b == 0)
+}
+
+// Decode a blob as text, or None if it is binary or too large.
+// Git stores files that have a `working-tree-encoding` attribute as UTF-8,
+// so only files without one need their encoding guessed.
+fn decode_blob(repo: &Repository, blob: &git2::Blob, path: &Path) -> Option<DecodedText> {
+    if blob.is_binary() || blob.size() > MAX_BLOB_SIZE {
+        return None;
+    }
+
+    let encoding = if working_tree_encoding(repo, path).is_some() {
+        UTF_8
+    } else {
+        encoding::detect(blob.content(), None)
+    };
+    Some((encoding::decode(blob.content(), encoding), encoding))
+}
+
+// Size and content of one side of a binary change, or None if that side does not exist
+fn binary_side<'a>(
+    file: &git2::DiffFile,
+    bytes: &'a Option<Vec<u8>>,
+) -> Option<(u64, Option<&'a [u8]>)> {
+    file.exists().then(|| {
+        let size = bytes
+            .as_ref()
+            .map_or(file.size(), |bytes| bytes.len() as u64);
+        (size, bytes.as_deref())
+    })
+}
+
 pub struct GitRepository {
     repo: Repository,
     commit_cache: RefCell<Option<Vec<Oid>>>,
@@ -180,9 +362,21 @@ pub struct GitRepository {
     // These modes are mutually exclusive based on CLI arguments.
     commit_index: RefCell<usize>,
     commit_range: RefCell<Option<Vec<Oid>>>,
+    range_spec: RefCell<Option<String>>,
     author_filter: Option<String>,
-    before_filter: Option<DateTime<Utc>>,
-    after_filter: Option<DateTime<Utc>>,
+    before_filter: Option<DateFilter>,
+    after_filter: Option<DateFilter>,
+    // Remaining commits of the current shuffle permutation, in play order
+    shuffle_queue: RefCell<VecDeque<Oid>>,
+    // Recently shown commits (oldest first), shared by random and shuffle playback
+    recent: RefCell<VecDeque<Oid>>,
+    // Persists `recent`, `shuffle_queue` and asc/desc cursors across sessions when set
+    state_store: Option<StateStore>,
+    diff_settings: DiffSettings,
+    // Replay the commits of a bumped submodule inside the parent commit
+    submodule_replay: bool,
+    // Picks random commits and shuffle permutations
+    rng: RefCell<StdRng>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -222,17 +416,49 @@ impl From<Delta> for FileStatus {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LineChangeType {
     Addition,
     Deletion,
     Context,
 }
 
+/// Terminator of a line, which is not part of its content
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    /// Last line of a file that does not end with a newline
+    NoNewline,
+}
+
+impl LineEnding {
+    /// Split a line into its content and terminator
+    pub fn split(line: &str) -> (&str, LineEnding) {
+        if let Some(content) = line.strip_suffix("\r\n") {
+            (content, LineEnding::CrLf)
+        } else if let Some(content) = line.strip_suffix('\n') {
+            (content, LineEnding::Lf)
+        } else {
+            (line, LineEnding::NoNewline)
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::NoNewline => "",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LineChange {
     pub change_type: LineChangeType,
     pub content: String,
+    pub line_ending: LineEnding,
     #[allow(dead_code)]
     pub old_line_no: Option<usize>,
     #[allow(dead_code)]
@@ -267,6 +493,37 @@ pub struct FileChange {
     pub hunks: Vec<DiffHunk>,
     #[allow(dead_code)]
     pub diff: String,
+    /// Package changes of an excluded lock file, if its format is known
+    pub dependency_changes: Vec<DependencyChange>,
+    /// Path whose extension selects syntax highlighting, when contents are a
+    /// transformed view (e.g. a notebook shown as Python)
+    pub syntax_path: Option<String>,
+    /// Preview shown instead of typing, for binary files and images
+    pub binary: Option<BinaryChange>,
+    /// Pointer change, for a submodule (gitlink) entry
+    pub submodule: Option<SubmoduleChange>,
+}
+
+/// A submodule pointer moved from one commit to another
+#[derive(Debug, Clone)]
+pub struct SubmoduleChange {
+    /// None when the submodule was added
+    pub old_id: Option<String>,
+    /// None when the submodule was removed
+    pub new_id: Option<String>,
+    /// Commits between the two pointers, newest first, or None if the
+    /// submodule is not checked out or does not have them
+    pub commits: Option<Vec<SubmoduleCommit>>,
+    /// Number of commits between the two pointers, including unlisted ones
+    pub total_commits: usize,
+    /// Latest commits replayed inline, oldest first
+    pub replays: Vec<CommitMetadata>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubmoduleCommit {
+    pub hash: String,
+    pub summary: String,
 }
 
 #[derive(Debug, Clone)]
@@ -276,6 +533,10 @@ pub struct CommitMetadata {
     pub date: DateTime<Utc>,
     pub message: String,
     pub changes: Vec<FileChange>,
+    /// Every change in the commit disappears when whitespace is ignored
+    pub whitespace_only: bool,
+    /// Name of the repository the commit comes from, when playing several
+    pub repository: Option<String>,
 }
 
 impl CommitMetadata {
@@ -308,12 +569,38 @@ impl GitRepository {
             commit_cache: RefCell::new(None),
             commit_index: RefCell::new(0),
             commit_range: RefCell::new(None),
+            range_spec: RefCell::new(None),
             author_filter: None,
             before_filter: None,
             after_filter: None,
+            shuffle_queue: RefCell::new(VecDeque::new()),
+            recent: RefCell::new(VecDeque::new()),
+            state_store: None,
+            diff_settings: DiffSettings::default(),
+            submodule_replay: false,
+            rng: RefCell::new(StdRng::from_rng(&mut rand::rng())),
         })
     }
 
+    /// Persist recently shown commits and the shuffle position in the given store,
+    /// restoring whatever a previous session saved for this repository.
+    pub fn set_state_store(&mut self, store: StateStore) {
+        let saved = store.load_repo(&self.state_key());
+        let parse = |hashes: &[String]| -> VecDeque<Oid> {
+            hashes
+                .iter()
+                .filter_map(|hash| Oid::from_str(hash).ok())
+                .collect()
+        };
+        *self.recent.borrow_mut() = parse(&saved.recent);
+        *self.shuffle_queue.borrow_mut() = parse(&saved.shuffle_queue);
+        self.state_store = Some(store);
+    }
+
+    fn state_key(&self) -> String {
+        self.repo.path().to_string_lossy().into_owned()
+    }
+
     pub fn get_commit(&self, hash: &str) -> Result<CommitMetadata> {
         let obj = self
             .repo
@@ -322,7 +609,7 @@ impl GitRepository {
 
         let commit = obj.peel_to_commit().context("Object is not a commit")?;
 
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn random_commit(&self) -> Result<CommitMetadata> {
@@ -331,12 +618,29 @@ impl GitRepository {
         let cache = self.commit_cache.borrow();
         let candidates = cache.as_ref().unwrap();
 
-        let selected_oid = candidates
-            .get(rand::rng().random_range(0..candidates.len()))
+        let selected_oid = self
+            .pick_random(candidates)
             .context("Failed to select random commit")?;
+        self.record_shown(selected_oid);
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        let commit = self.repo.find_commit(selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    /// Plays a random permutation of all commits before any commit repeats.
+    pub fn next_shuffle_commit(&self) -> Result<CommitMetadata> {
+        self.populate_cache()?;
+
+        let cache = self.commit_cache.borrow();
+        let candidates = cache.as_ref().unwrap();
+
+        let selected_oid = self
+            .next_shuffled(candidates)
+            .context("No non-merge commits found in repository")?;
+        self.record_shown(selected_oid);
+
+        let commit = self.repo.find_commit(selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn next_asc_commit(&self) -> Result<CommitMetadata> {
@@ -361,9 +665,10 @@ impl GitRepository {
             .context("Failed to select commit")?;
 
         *index += 1;
+        self.save_cursor(PlaybackDirection::Asc, *selected_oid);
 
         let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn next_desc_commit(&self) -> Result<CommitMetadata> {
@@ -385,34 +690,196 @@ impl GitRepository {
         let selected_oid = candidates.get(*index).context("Failed to select commit")?;
 
         *index += 1;
+        self.save_cursor(PlaybackDirection::Desc, *selected_oid);
 
         let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn reset_index(&self) {
         *self.commit_index.borrow_mut() = 0;
     }
 
+    pub fn set_diff_settings(&mut self, settings: DiffSettings) {
+        self.diff_settings = settings;
+    }
+
+    /// Replay the new commits of a bumped submodule inside the parent commit,
+    /// when the submodule is checked out.
+    pub fn set_submodule_replay(&mut self, enabled: bool) {
+        self.submodule_replay = enabled;
+    }
+
+    /// Sets the generator behind random and shuffle playback, so that the
+    /// same seed plays the same commits.
+    pub fn set_rng(&mut self, rng: StdRng) {
+        self.rng = RefCell::new(rng);
+    }
+
+    /// Calls `next` until it yields a commit that is not skipped because it
+    /// only changes whitespace.
+    pub fn next_playable<F>(&self, next: F) -> Result<CommitMetadata>
+    where
+        F: Fn(&Self) -> Result<CommitMetadata>,
+    {
+        if self.diff_settings.whitespace_commits != WhitespaceCommits::Skip {
+            return next(self);
+        }
+
+        let attempts = match self.commit_range.borrow().as_ref() {
+            Some(range) => range.len(),
+            None => {
+                self.populate_cache()?;
+                self.commit_cache.borrow().as_ref().map_or(0, Vec::len)
+            }
+        };
+        for _ in 0..attempts.max(1) {
+            let metadata = next(self)?;
+            if !metadata.whitespace_only {
+                return Ok(metadata);
+            }
+        }
+        anyhow::bail!("No commits left that change more than whitespace")
+    }
+
     pub fn set_author_filter(&mut self, author: Option<String>) {
         self.author_filter = author;
     }
 
-    pub fn set_before_filter(&mut self, before: Option<DateTime<Utc>>) {
-        self.before_filter = before;
+    pub fn set_before_filter(&mut self, before: Option<&str>) -> Result<()> {
+        self.before_filter = before.map(DateFilter::parse).transpose()?;
+        Ok(())
     }
 
-    pub fn set_after_filter(&mut self, after: Option<DateTime<Utc>>) {
-        self.after_filter = after;
+    pub fn set_after_filter(&mut self, after: Option<&str>) -> Result<()> {
+        self.after_filter = after.map(DateFilter::parse).transpose()?;
+        Ok(())
     }
 
     pub fn set_commit_range(&self, range: &str) -> Result<()> {
         let commits = self.parse_commit_range(range)?;
         *self.commit_range.borrow_mut() = Some(commits);
+        *self.range_spec.borrow_mut() = Some(range.to_string());
         *self.commit_index.borrow_mut() = 0;
         Ok(())
     }
 
+    /// Position asc/desc playback after the commit a previous session stopped at.
+    ///
+    /// Cursors are saved per order and filter combination. If the saved commit was
+    /// rewritten away, playback continues from the first commit authored after it.
+    pub fn resume(&self, direction: PlaybackDirection) -> Result<ResumeOutcome> {
+        let Some(store) = &self.state_store else {
+            return Ok(ResumeOutcome::NoCursor);
+        };
+        let saved = store.load_repo(&self.state_key());
+        let Some(cursor) = saved.cursors.get(&self.cursor_key(direction)) else {
+            return Ok(ResumeOutcome::NoCursor);
+        };
+
+        let order = self.play_order(direction)?;
+        let cursor_oid = Oid::from_str(&cursor.commit).ok();
+
+        let (next_index, outcome) = match order.iter().position(|oid| Some(*oid) == cursor_oid) {
+            Some(pos) => (pos + 1, ResumeOutcome::Resumed),
+            None => {
+                let is_after = |oid: &Oid| {
+                    let time = self.author_time(*oid);
+                    match direction {
+                        PlaybackDirection::Asc => time > cursor.time,
+                        PlaybackDirection::Desc => time < cursor.time,
+                    }
+                };
+                let pos = order.iter().position(is_after).unwrap_or(order.len());
+                (pos, ResumeOutcome::Rewritten)
+            }
+        };
+
+        if next_index >= order.len() {
+            *self.commit_index.borrow_mut() = 0;
+            return Ok(ResumeOutcome::Restarted);
+        }
+
+        *self.commit_index.borrow_mut() = next_index;
+        Ok(outcome)
+    }
+
+    /// Number of commits that can be played, within the range and filters.
+    pub fn commit_count(&self) -> Result<usize> {
+        Ok(self.play_order(PlaybackDirection::Asc)?.len())
+    }
+
+    /// Hashes of every commit sequential playback would visit, oldest first.
+    pub fn commit_hashes(&self) -> Result<Vec<String>> {
+        Ok(self
+            .play_order(PlaybackDirection::Asc)?
+            .iter()
+            .map(Oid::to_string)
+            .collect())
+    }
+
+    // Candidate commits in the order asc/desc playback visits them
+    fn play_order(&self, direction: PlaybackDirection) -> Result<Vec<Oid>> {
+        let mut order = if let Some(range) = self.commit_range.borrow().as_ref() {
+            // Range commits are stored oldest first
+            range.clone()
+        } else {
+            self.populate_cache()?;
+            // Cache is stored newest first
+            let mut cache = self.commit_cache.borrow().clone().unwrap_or_default();
+            cache.reverse();
+            cache
+        };
+
+        if direction == PlaybackDirection::Desc {
+            order.reverse();
+        }
+        Ok(order)
+    }
+
+    fn author_time(&self, oid: Oid) -> i64 {
+        self.repo
+            .find_commit(oid)
+            .map(|commit| commit.author().when().seconds())
+            .unwrap_or(0)
+    }
+
+    // Cursor key combining playback order with every active commit filter
+    fn cursor_key(&self, direction: PlaybackDirection) -> String {
+        let mut key = direction.as_str().to_string();
+        if let Some(range) = self.range_spec.borrow().as_ref() {
+            key.push_str(&format!(" range={}", range));
+        }
+        if let Some(author) = &self.author_filter {
+            key.push_str(&format!(" author={}", author));
+        }
+        // Relative dates resolve differently on every run, so the key uses
+        // the filters as given
+        if let Some(before) = &self.before_filter {
+            key.push_str(&format!(" before={}", before.spec));
+        }
+        if let Some(after) = &self.after_filter {
+            key.push_str(&format!(" after={}", after.spec));
+        }
+        key
+    }
+
+    // Best-effort, like `save_state`
+    fn save_cursor(&self, direction: PlaybackDirection, oid: Oid) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+
+        let key = self.cursor_key(direction);
+        let cursor = PlaybackCursor {
+            commit: oid.to_string(),
+            time: self.author_time(oid),
+        };
+        let _ = store.update_repo(&self.state_key(), |state| {
+            state.cursors.insert(key, cursor);
+        });
+    }
+
     pub fn next_range_commit_asc(&self) -> Result<CommitMetadata> {
         let range = self.commit_range.borrow();
         let commits = range.as_ref().context("Commit range not set")?;
@@ -428,9 +895,10 @@ impl GitRepository {
 
         let selected_oid = commits.get(*index).context("Failed to select commit")?;
         *index += 1;
+        self.save_cursor(PlaybackDirection::Asc, *selected_oid);
 
         let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn next_range_commit_desc(&self) -> Result<CommitMetadata> {
@@ -450,9 +918,10 @@ impl GitRepository {
         let desc_index = commits.len() - 1 - *index;
         let selected_oid = commits.get(desc_index).context("Failed to select commit")?;
         *index += 1;
+        self.save_cursor(PlaybackDirection::Desc, *selected_oid);
 
         let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn random_range_commit(&self) -> Result<CommitMetadata> {
@@ -463,12 +932,121 @@ impl GitRepository {
             anyhow::bail!("No commits in range");
         }
 
-        let selected_oid = commits
-            .get(rand::rng().random_range(0..commits.len()))
+        let selected_oid = self
+            .pick_random(commits)
             .context("Failed to select random commit")?;
+        self.record_shown(selected_oid);
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        let commit = self.repo.find_commit(selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn next_range_commit_shuffle(&self) -> Result<CommitMetadata> {
+        let range = self.commit_range.borrow();
+        let commits = range.as_ref().context("Commit range not set")?;
+
+        let selected_oid = self.next_shuffled(commits).context("No commits in range")?;
+        self.record_shown(selected_oid);
+
+        let commit = self.repo.find_commit(selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    // Most recently shown commits of the pool, capped at half of it so that
+    // there is always something left to choose from
+    fn recent_in_pool(&self, candidates: &[Oid]) -> HashSet<Oid> {
+        let pool: HashSet<&Oid> = candidates.iter().collect();
+        self.recent
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|oid| pool.contains(oid))
+            .take(candidates.len() / 2)
+            .copied()
+            .collect()
+    }
+
+    // Pick a random commit, avoiding recently shown ones while enough others remain
+    fn pick_random(&self, candidates: &[Oid]) -> Option<Oid> {
+        let recent = self.recent_in_pool(candidates);
+        let fresh: Vec<Oid> = candidates
+            .iter()
+            .filter(|oid| !recent.contains(oid))
+            .copied()
+            .collect();
+
+        let pool = if fresh.is_empty() { candidates } else { &fresh };
+        if pool.is_empty() {
+            return None;
+        }
+        let index = self.rng.borrow_mut().random_range(0..pool.len());
+        pool.get(index).copied()
+    }
+
+    // Take the next commit of the shuffle permutation, starting a new one when exhausted
+    fn next_shuffled(&self, candidates: &[Oid]) -> Option<Oid> {
+        let pool: HashSet<Oid> = candidates.iter().copied().collect();
+        let mut queue = self.shuffle_queue.borrow_mut();
+
+        // Drop commits that left the pool (history rewritten or filters changed)
+        queue.retain(|oid| pool.contains(oid));
+
+        if queue.is_empty() {
+            *queue = self.build_shuffle_queue(candidates);
+        }
+
+        queue.pop_front()
+    }
+
+    // New permutation of the pool; recently shown commits are moved to the end,
+    // least recently shown first, so they don't repeat across a permutation boundary
+    fn build_shuffle_queue(&self, candidates: &[Oid]) -> VecDeque<Oid> {
+        let recent_set = self.recent_in_pool(candidates);
+
+        let mut fresh: Vec<Oid> = candidates
+            .iter()
+            .filter(|oid| !recent_set.contains(oid))
+            .copied()
+            .collect();
+        fresh.shuffle(&mut *self.rng.borrow_mut());
+
+        let recent = self.recent.borrow();
+        let held_back = recent
+            .iter()
+            .filter(|oid| recent_set.contains(oid))
+            .copied();
+
+        fresh.into_iter().chain(held_back).collect()
+    }
+
+    fn record_shown(&self, oid: Oid) {
+        {
+            let mut recent = self.recent.borrow_mut();
+            recent.retain(|shown| *shown != oid);
+            recent.push_back(oid);
+            while recent.len() > MAX_RECENT_COMMITS {
+                recent.pop_front();
+            }
+        }
+        self.save_state();
+    }
+
+    // Best-effort: playback continues even if the state file can't be written
+    fn save_state(&self) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+
+        let to_strings = |oids: &VecDeque<Oid>| -> Vec<String> {
+            oids.iter().map(|oid| oid.to_string()).collect()
+        };
+        let recent = to_strings(&self.recent.borrow());
+        let shuffle_queue = to_strings(&self.shuffle_queue.borrow());
+
+        let _ = store.update_repo(&self.state_key(), |state| {
+            state.recent = recent;
+            state.shuffle_queue = shuffle_queue;
+        });
     }
 
     // Collect non-merge commits from a revwalk, applying author and date filters if set
@@ -488,8 +1066,8 @@ impl GitRepository {
                     }
                     if !matches_date_filter(
                         &commit,
-                        self.before_filter.as_ref(),
-                        self.after_filter.as_ref(),
+                        self.before_filter.as_ref().map(|filter| &filter.date),
+                        self.after_filter.as_ref().map(|filter| &filter.date),
                     )? {
                         continue;
                     }
@@ -567,10 +1145,7 @@ impl GitRepository {
         Ok(())
     }
 
-    fn extract_metadata_with_changes(
-        repo: &Repository,
-        commit: &Git2Commit,
-    ) -> Result<CommitMetadata> {
+    fn extract_metadata_with_changes(&self, commit: &Git2Commit) -> Result<CommitMetadata> {
         let hash = commit.id().to_string();
         let author = commit.author();
         let author_name = author.name().unwrap_or("Unknown").to_string();
@@ -578,7 +1153,16 @@ impl GitRepository {
         let date = DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
         let message = commit.message().unwrap_or("").trim().to_string();
 
-        let changes = Self::extract_changes(repo, commit)?;
+        let mut changes = self.extract_changes(commit)?;
+        let whitespace_only = self.diff_settings.whitespace_commits != WhitespaceCommits::Show
+            && self.is_whitespace_only(commit)?;
+
+        if whitespace_only && self.diff_settings.whitespace_commits == WhitespaceCommits::Compact {
+            for change in &mut changes {
+                change.is_excluded = true;
+                change.exclusion_reason = Some("whitespace only".to_string());
+            }
+        }
 
         Ok(CommitMetadata {
             hash,
@@ -586,10 +1170,46 @@ impl GitRepository {
             date,
             message,
             changes,
+            whitespace_only,
+            repository: None,
         })
     }
 
-    fn extract_changes(repo: &Repository, commit: &Git2Commit) -> Result<Vec<FileChange>> {
+    // True if the commit modifies files but every change disappears when
+    // whitespace and blank lines are ignored
+    fn is_whitespace_only(&self, commit: &Git2Commit) -> Result<bool> {
+        if commit.parent_count() == 0 {
+            return Ok(false);
+        }
+        let commit_tree = commit.tree().context("Failed to get commit tree")?;
+        let parent_tree = commit.parent(0)?.tree()?;
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.ignore_whitespace(true).ignore_blank_lines(true);
+        let diff = self.repo.diff_tree_to_tree(
+            Some(&parent_tree),
+            Some(&commit_tree),
+            Some(&mut diff_opts),
+        )?;
+
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+        for (i, delta) in diff.deltas().enumerate() {
+            if delta.status() != Delta::Modified || delta.new_file().is_binary() {
+                return Ok(false);
+            }
+            if let Ok(Some(patch)) = git2::Patch::from_diff(&diff, i) {
+                if patch.num_hunks() > 0 {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn extract_changes(&self, commit: &Git2Commit) -> Result<Vec<FileChange>> {
+        let repo = &self.repo;
         let commit_tree = commit.tree().context("Failed to get commit tree")?;
         let parent_tree = if commit.parent_count() > 0 {
             match commit.parent(0).and_then(|p| p.tree()) {
@@ -600,8 +1220,7 @@ impl GitRepository {
             None
         };
 
-        let mut diff_opts = DiffOptions::new();
-        diff_opts.context_lines(3);
+        let mut diff_opts = self.diff_settings.diff_options();
 
         let diff = match repo.diff_tree_to_tree(
             parent_tree.as_ref(),
@@ -640,105 +1259,40 @@ impl GitRepository {
 
             let is_binary = delta.new_file().is_binary() || delta.old_file().is_binary();
 
-            let old_content = if let Some(parent_tree) = parent_tree.as_ref() {
-                if let Some(old_file_path) = delta.old_file().path() {
-                    parent_tree
-                        .get_path(old_file_path)
-                        .ok()
-                        .and_then(|entry| repo.find_blob(entry.id()).ok())
-                        .and_then(|blob| {
-                            if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
-                                Some(String::from_utf8_lossy(blob.content()).to_string())
-                            } else {
-                                None
-                            }
-                        })
-                } else {
-                    None
-                }
-            } else {
+            let view = if is_binary {
                 None
-            };
-
-            let new_content = if let Some(new_file_path) = delta.new_file().path() {
-                commit_tree
-                    .get_path(new_file_path)
-                    .ok()
-                    .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(|blob| {
-                        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
-                            Some(String::from_utf8_lossy(blob.content()).to_string())
-                        } else {
-                            None
-                        }
-                    })
             } else {
-                None
+                self.transform_delta(&path, &delta)
             };
-
-            let mut hunks = Vec::new();
-            let mut diff_text = String::new();
-
-            if let Ok(Some(mut patch)) = git2::Patch::from_diff(&diff, i) {
-                if let Ok(patch_str) = patch.to_buf() {
-                    diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
+            let (hunks, diff_text, old_content, new_content, syntax_path) = match view {
+                Some(TransformedView {
+                    hunks,
+                    diff,
+                    old_content,
+                    new_content,
+                    syntax_path,
+                }) => (hunks, diff, old_content, new_content, syntax_path),
+                None => {
+                    let old_text = parent_tree.as_ref().and_then(|tree| {
+                        let path = delta.old_file().path()?;
+                        let entry = tree.get_path(path).ok()?;
+                        let blob = repo.find_blob(entry.id()).ok()?;
+                        decode_blob(repo, &blob, path)
+                    });
+
+                    let new_text = delta.new_file().path().and_then(|path| {
+                        let entry = commit_tree.get_path(path).ok()?;
+                        let blob = repo.find_blob(entry.id()).ok()?;
+                        decode_blob(repo, &blob, path)
+                    });
+
+                    let (hunks, diff_text) =
+                        Self::extract_hunks_from_diff(&diff, i, is_binary, &old_text, &new_text)?;
+                    let old_content = old_text.map(|(text, _)| text);
+                    let new_content = new_text.map(|(text, _)| text);
+                    (hunks, diff_text, old_content, new_content, None)
                 }
-
-                if !is_binary {
-                    for hunk_idx in 0..patch.num_hunks() {
-                        if let Ok((hunk, _hunk_lines)) = patch.hunk(hunk_idx) {
-                            let mut lines = Vec::new();
-                            let num_lines = patch.num_lines_in_hunk(hunk_idx).unwrap_or(0);
-
-                            let mut old_line_no = hunk.old_start() as usize;
-                            let mut new_line_no = hunk.new_start() as usize;
-
-                            for line_idx in 0..num_lines {
-                                if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
-                                    let content =
-                                        String::from_utf8_lossy(line.content()).to_string();
-                                    let origin = line.origin();
-
-                                    let (change_type, old_no, new_no) = match origin {
-                                        '+' => {
-                                            let no = new_line_no;
-                                            new_line_no += 1;
-                                            (LineChangeType::Addition, None, Some(no))
-                                        }
-                                        '-' => {
-                                            let no = old_line_no;
-                                            old_line_no += 1;
-                                            (LineChangeType::Deletion, Some(no), None)
-                                        }
-                                        _ => {
-                                            let old_no = old_line_no;
-                                            let new_no = new_line_no;
-                                            old_line_no += 1;
-                                            new_line_no += 1;
-                                            (LineChangeType::Context, Some(old_no), Some(new_no))
-                                        }
-                                    };
-
-                                    lines.push(LineChange {
-                                        change_type,
-                                        content,
-                                        old_line_no: old_no,
-                                        new_line_no: new_no,
-                                    });
-                                }
-                            }
-
-                            hunks.push(DiffHunk {
-                                old_start: hunk.old_start() as usize,
-                                old_lines: hunk.old_lines() as usize,
-                                new_start: hunk.new_start() as usize,
-                                new_lines: hunk.new_lines() as usize,
-                                lines,
-                            });
-                        }
-                    }
-                }
-            }
+            };
 
             // Calculate total changed lines (additions + deletions)
             let total_changed_lines: usize = hunks
@@ -747,10 +1301,24 @@ impl GitRepository {
                 .filter(|line| !matches!(line.change_type, LineChangeType::Context))
                 .count();
 
+            // Binary content is only detected while the patch is generated
+            let is_binary = is_binary
+                || diff.get_delta(i).is_some_and(|delta| {
+                    delta.new_file().is_binary() || delta.old_file().is_binary()
+                });
+            let binary = self.binary_change(&path, &delta, is_binary);
+            let submodule = self.submodule_change(&path, &delta);
+
+            let dependency_changes = if should_exclude_file(&path) {
+                self.dependency_changes(&path, &delta)
+            } else {
+                Vec::new()
+            };
+
             // Determine exclusion reason
             let (is_excluded, exclusion_reason) = if should_exclude_file(&path) {
                 (true, Some("lock/generated file".to_string()))
-            } else if total_changed_lines > MAX_CHANGE_LINES {
+            } else if binary.is_none() && total_changed_lines > MAX_CHANGE_LINES {
                 (
                     true,
                     Some(format!("too many changes ({} lines)", total_changed_lines)),
@@ -770,6 +1338,10 @@ impl GitRepository {
                 new_content,
                 hunks,
                 diff: diff_text,
+                dependency_changes,
+                syntax_path,
+                binary,
+                submodule,
             });
         }
 
@@ -797,6 +1369,8 @@ impl GitRepository {
             date: Utc::now(),
             message: message.to_string(),
             changes,
+            whitespace_only: false,
+            repository: None,
         })
     }
 
@@ -813,8 +1387,7 @@ impl GitRepository {
             .index()
             .context("Failed to get repository index")?;
 
-        let mut diff_opts = DiffOptions::new();
-        diff_opts.context_lines(3);
+        let mut diff_opts = self.diff_settings.diff_options();
 
         let diff = self
             .repo
@@ -831,8 +1404,7 @@ impl GitRepository {
             .index()
             .context("Failed to get repository index")?;
 
-        let mut diff_opts = DiffOptions::new();
-        diff_opts.context_lines(3);
+        let mut diff_opts = self.diff_settings.diff_options();
         diff_opts.include_untracked(true);
 
         let diff = self
@@ -852,15 +1424,15 @@ impl GitRepository {
         new_tree: Option<&git2::Tree>,
     ) -> Result<Vec<FileChange>> {
         self.extract_changes_from_diff_with_content(diff, |delta| {
-            let old_content = old_tree
+            let old_text = old_tree
                 .and_then(|tree| self.get_blob_content_from_tree(tree, delta.old_file().path()));
-            let new_content = if let Some(tree) = new_tree {
+            let new_text = if let Some(tree) = new_tree {
                 self.get_blob_content_from_tree(tree, delta.new_file().path())
             } else {
                 // For staged changes, get from index
                 self.get_index_content(delta.new_file().path())
             };
-            (old_content, new_content)
+            (old_text, new_text)
         })
     }
 
@@ -871,9 +1443,9 @@ impl GitRepository {
         index: &git2::Index,
     ) -> Result<Vec<FileChange>> {
         self.extract_changes_from_diff_with_content(diff, |delta| {
-            let old_content = self.get_index_content_from(index, delta.old_file().path());
-            let new_content = self.get_workdir_content(delta.new_file().path());
-            (old_content, new_content)
+            let old_text = self.get_index_content_from(index, delta.old_file().path());
+            let new_text = self.get_workdir_content(delta.new_file().path());
+            (old_text, new_text)
         })
     }
 
@@ -884,7 +1456,7 @@ impl GitRepository {
         get_content: F,
     ) -> Result<Vec<FileChange>>
     where
-        F: Fn(&git2::DiffDelta) -> (Option<String>, Option<String>),
+        F: Fn(&git2::DiffDelta) -> (Option<DecodedText>, Option<DecodedText>),
     {
         let mut changes = Vec::new();
 
@@ -913,8 +1485,28 @@ impl GitRepository {
             };
 
             let is_binary = delta.new_file().is_binary() || delta.old_file().is_binary();
-            let (old_content, new_content) = get_content(&delta);
-            let (hunks, diff_text) = self.extract_hunks_from_diff(diff, i, is_binary)?;
+            let view = if is_binary {
+                None
+            } else {
+                self.transform_delta(&path, &delta)
+            };
+            let (hunks, diff_text, old_content, new_content, syntax_path) = match view {
+                Some(TransformedView {
+                    hunks,
+                    diff,
+                    old_content,
+                    new_content,
+                    syntax_path,
+                }) => (hunks, diff, old_content, new_content, syntax_path),
+                None => {
+                    let (old_text, new_text) = get_content(&delta);
+                    let (hunks, diff_text) =
+                        Self::extract_hunks_from_diff(diff, i, is_binary, &old_text, &new_text)?;
+                    let old_content = old_text.map(|(text, _)| text);
+                    let new_content = new_text.map(|(text, _)| text);
+                    (hunks, diff_text, old_content, new_content, None)
+                }
+            };
 
             // Calculate total changed lines
             let total_changed_lines: usize = hunks
@@ -923,9 +1515,23 @@ impl GitRepository {
                 .filter(|line| !matches!(line.change_type, LineChangeType::Context))
                 .count();
 
+            // Binary content is only detected while the patch is generated
+            let is_binary = is_binary
+                || diff.get_delta(i).is_some_and(|delta| {
+                    delta.new_file().is_binary() || delta.old_file().is_binary()
+                });
+            let binary = self.binary_change(&path, &delta, is_binary);
+            let submodule = self.submodule_change(&path, &delta);
+
+            let dependency_changes = if should_exclude_file(&path) {
+                self.dependency_changes(&path, &delta)
+            } else {
+                Vec::new()
+            };
+
             let (is_excluded, exclusion_reason) = if should_exclude_file(&path) {
                 (true, Some("lock/generated file".to_string()))
-            } else if total_changed_lines > MAX_CHANGE_LINES {
+            } else if binary.is_none() && total_changed_lines > MAX_CHANGE_LINES {
                 (
                     true,
                     Some(format!("too many changes ({} lines)", total_changed_lines)),
@@ -945,103 +1551,335 @@ impl GitRepository {
                 new_content,
                 hunks,
                 diff: diff_text,
+                dependency_changes,
+                syntax_path,
+                binary,
+                submodule,
             });
         }
 
         Ok(changes)
     }
 
+    /// Diff the transformed views of a delta when a content transformer
+    /// handles the file, instead of its raw contents.
+    fn transform_delta(&self, path: &str, delta: &git2::DiffDelta) -> Option<TransformedView> {
+        let transformer = transform::find(path)?;
+        let transform_side = |file: git2::DiffFile| -> Option<Option<transform::Transformed>> {
+            if !file.exists() {
+                return Some(None);
+            }
+            transformer.transform(&self.full_text(&file)?).map(Some)
+        };
+        let old = transform_side(delta.old_file())?;
+        let new = transform_side(delta.new_file())?;
+
+        let old_text = old.as_ref().map_or("", |view| view.text.as_str());
+        let new_text = new.as_ref().map_or("", |view| view.text.as_str());
+        let mut opts = self.diff_settings.diff_options();
+        let (hunks, diff) = {
+            let mut patch = git2::Patch::from_buffers(
+                old_text.as_bytes(),
+                Some(Path::new(path)),
+                new_text.as_bytes(),
+                Some(Path::new(path)),
+                Some(&mut opts),
+            )
+            .ok()?;
+            Self::extract_hunks_from_patch(&mut patch, false, UTF_8, UTF_8)
+        };
+
+        let extension = new
+            .as_ref()
+            .or(old.as_ref())
+            .and_then(|view| view.extension.clone());
+        Some(TransformedView {
+            old_content: old.map(|view| view.text),
+            new_content: new.map(|view| view.text),
+            hunks,
+            diff,
+            syntax_path: extension.map(|ext| format!("{}.{}", path, ext)),
+        })
+    }
+
+    /// Summarize the package changes of a lock file delta.
+    /// Lock files are read without the usual blob size limit.
+    fn dependency_changes(&self, path: &str, delta: &git2::DiffDelta) -> Vec<DependencyChange> {
+        // Other excluded files are not read at all
+        if !lockfile::is_supported(path) {
+            return Vec::new();
+        }
+        let (Some(old), Some(new)) = (
+            self.full_text(&delta.old_file()),
+            self.full_text(&delta.new_file()),
+        ) else {
+            return Vec::new();
+        };
+        lockfile::diff(path, &old, &new).unwrap_or_default()
+    }
+
+    // Text of one side of a delta without the blob size limit; empty if that side does not exist
+    fn full_text(&self, file: &git2::DiffFile) -> Option<String> {
+        if !file.exists() {
+            return Some(String::new());
+        }
+        String::from_utf8(self.file_bytes(file)?).ok()
+    }
+
+    // Raw content of one side of a delta, or None if it is too large to read
+    fn file_bytes(&self, file: &git2::DiffFile) -> Option<Vec<u8>> {
+        if file.size() > MAX_PARSED_FILE_SIZE as u64 {
+            return None;
+        }
+
+        let bytes = if file.id().is_zero() {
+            // Unhashed working tree file
+            std::fs::read(self.repo.workdir()?.join(file.path()?)).ok()?
+        } else {
+            self.repo.find_blob(file.id()).ok()?.content().to_vec()
+        };
+        (bytes.len() <= MAX_PARSED_FILE_SIZE).then_some(bytes)
+    }
+
+    /// Pointer change of a submodule, with the commits it brings in when the
+    /// submodule is checked out.
+    fn submodule_change(&self, path: &str, delta: &git2::DiffDelta) -> Option<SubmoduleChange> {
+        let (old_file, new_file) = (delta.old_file(), delta.new_file());
+        let is_gitlink = |file: &git2::DiffFile| file.mode() == git2::FileMode::Commit;
+        if !is_gitlink(&old_file) && !is_gitlink(&new_file) {
+            return None;
+        }
+
+        let id =
+            |file: &git2::DiffFile| (is_gitlink(file) && !file.id().is_zero()).then(|| file.id());
+        let (old_id, new_id) = (id(&old_file), id(&new_file));
+
+        let submodule = self
+            .repo
+            .workdir()
+            .and_then(|workdir| Repository::open(workdir.join(path)).ok());
+        let range = match (&submodule, old_id, new_id) {
+            (Some(submodule), Some(old_id), Some(new_id)) => {
+                Self::submodule_range(submodule, old_id, new_id).ok()
+            }
+            _ => None,
+        };
+
+        let total_commits = range.as_ref().map_or(0, Vec::len);
+        let replays = match &range {
+            Some(range) if self.submodule_replay => self.submodule_replays(path, range),
+            _ => Vec::new(),
+        };
+        let commits = range.map(|range| {
+            range
+                .iter()
+                .take(MAX_SUBMODULE_COMMITS)
+                .filter_map(|&oid| {
+                    let commit = submodule.as_ref()?.find_commit(oid).ok()?;
+                    Some(SubmoduleCommit {
+                        hash: oid.to_string(),
+                        summary: commit.summary().unwrap_or_default().to_string(),
+                    })
+                })
+                .collect()
+        });
+
+        Some(SubmoduleChange {
+            old_id: old_id.map(|id| id.to_string()),
+            new_id: new_id.map(|id| id.to_string()),
+            commits,
+            total_commits,
+            replays,
+        })
+    }
+
+    // Commits reachable from `new_id` but not `old_id`, newest first
+    fn submodule_range(submodule: &Repository, old_id: Oid, new_id: Oid) -> Result<Vec<Oid>> {
+        let mut revwalk = submodule.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+        revwalk.push(new_id)?;
+        revwalk.hide(old_id)?;
+        revwalk
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to walk submodule commits")
+    }
+
+    // Latest commits of a submodule range as metadata, oldest first. The nested
+    // repository does not replay its own submodules.
+    fn submodule_replays(&self, path: &str, range: &[Oid]) -> Vec<CommitMetadata> {
+        let Some(workdir) = self.repo.workdir() else {
+            return Vec::new();
+        };
+        let Ok(mut submodule) = GitRepository::open(workdir.join(path)) else {
+            return Vec::new();
+        };
+        submodule.set_diff_settings(self.diff_settings.clone());
+
+        range
+            .iter()
+            .take(MAX_SUBMODULE_REPLAYS)
+            .rev()
+            .filter_map(|oid| submodule.get_commit(&oid.to_string()).ok())
+            .collect()
+    }
+
+    /// Preview of a binary or image file change.
+    ///
+    /// Text files that are not images get no preview, and neither do SVG files
+    /// that fail to render, so they are typed out as usual.
+    fn binary_change(
+        &self,
+        path: &str,
+        delta: &git2::DiffDelta,
+        is_binary: bool,
+    ) -> Option<BinaryChange> {
+        if !is_binary && !preview::is_image_path(path) {
+            return None;
+        }
+
+        let (old_file, new_file) = (delta.old_file(), delta.new_file());
+        let old_bytes = old_file
+            .exists()
+            .then(|| self.file_bytes(&old_file))
+            .flatten();
+        let new_bytes = new_file
+            .exists()
+            .then(|| self.file_bytes(&new_file))
+            .flatten();
+        let change = BinaryChange::new(
+            path,
+            binary_side(&old_file, &old_bytes),
+            binary_side(&new_file, &new_bytes),
+        );
+        (is_binary || change.is_image()).then_some(change)
+    }
+
     /// Get blob content from a tree by path
     fn get_blob_content_from_tree(
         &self,
         tree: &git2::Tree,
         path: Option<&std::path::Path>,
-    ) -> Option<String> {
+    ) -> Option<DecodedText> {
         let path = path?;
         let entry = tree.get_path(path).ok()?;
         let blob = self.repo.find_blob(entry.id()).ok()?;
-        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
-            Some(String::from_utf8_lossy(blob.content()).to_string())
-        } else {
-            None
-        }
+        decode_blob(&self.repo, &blob, path)
     }
 
     /// Extract hunks from a diff at given delta index
+    ///
+    /// Removed and added lines are decoded with the encoding of the old and new
+    /// content respectively, and split from their line terminators.
     fn extract_hunks_from_diff(
-        &self,
         diff: &git2::Diff,
         delta_idx: usize,
         is_binary: bool,
+        old_text: &Option<DecodedText>,
+        new_text: &Option<DecodedText>,
     ) -> Result<(Vec<DiffHunk>, String)> {
+        let new_encoding = new_text.as_ref().map_or(UTF_8, |(_, encoding)| *encoding);
+        let old_encoding = old_text
+            .as_ref()
+            .map_or(new_encoding, |(_, encoding)| *encoding);
+
+        match git2::Patch::from_diff(diff, delta_idx) {
+            Ok(Some(mut patch)) => Ok(Self::extract_hunks_from_patch(
+                &mut patch,
+                is_binary,
+                old_encoding,
+                new_encoding,
+            )),
+            _ => Ok((Vec::new(), String::new())),
+        }
+    }
+
+    /// Extract hunks and the patch text from a patch
+    fn extract_hunks_from_patch(
+        patch: &mut git2::Patch,
+        is_binary: bool,
+        old_encoding: &'static Encoding,
+        new_encoding: &'static Encoding,
+    ) -> (Vec<DiffHunk>, String) {
         let mut hunks = Vec::new();
         let mut diff_text = String::new();
 
-        if let Ok(Some(mut patch)) = git2::Patch::from_diff(diff, delta_idx) {
-            if let Ok(patch_str) = patch.to_buf() {
-                diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
-            }
+        if let Ok(patch_str) = patch.to_buf() {
+            diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
+        }
 
-            if !is_binary {
-                for hunk_idx in 0..patch.num_hunks() {
-                    if let Ok((hunk, _hunk_lines)) = patch.hunk(hunk_idx) {
-                        let mut lines = Vec::new();
-                        let num_lines = patch.num_lines_in_hunk(hunk_idx).unwrap_or(0);
-
-                        let mut old_line_no = hunk.old_start() as usize;
-                        let mut new_line_no = hunk.new_start() as usize;
-
-                        for line_idx in 0..num_lines {
-                            if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
-                                let content = String::from_utf8_lossy(line.content()).to_string();
-                                let origin = line.origin();
-
-                                let (change_type, old_no, new_no) = match origin {
-                                    '+' => {
-                                        let no = new_line_no;
-                                        new_line_no += 1;
-                                        (LineChangeType::Addition, None, Some(no))
-                                    }
-                                    '-' => {
-                                        let no = old_line_no;
-                                        old_line_no += 1;
-                                        (LineChangeType::Deletion, Some(no), None)
-                                    }
-                                    _ => {
-                                        let old_no = old_line_no;
-                                        let new_no = new_line_no;
-                                        old_line_no += 1;
-                                        new_line_no += 1;
-                                        (LineChangeType::Context, Some(old_no), Some(new_no))
+        if !is_binary {
+            for hunk_idx in 0..patch.num_hunks() {
+                if let Ok((hunk, _hunk_lines)) = patch.hunk(hunk_idx) {
+                    let mut lines: Vec<LineChange> = Vec::new();
+                    let num_lines = patch.num_lines_in_hunk(hunk_idx).unwrap_or(0);
+
+                    let mut old_line_no = hunk.old_start() as usize;
+                    let mut new_line_no = hunk.new_start() as usize;
+
+                    for line_idx in 0..num_lines {
+                        if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
+                            let origin = line.origin();
+
+                            let (change_type, old_no, new_no) = match origin {
+                                '+' => {
+                                    let no = new_line_no;
+                                    new_line_no += 1;
+                                    (LineChangeType::Addition, None, Some(no))
+                                }
+                                '-' => {
+                                    let no = old_line_no;
+                                    old_line_no += 1;
+                                    (LineChangeType::Deletion, Some(no), None)
+                                }
+                                // "\ No newline at end of file" applies to the previous line
+                                '=' | '>' | '<' => {
+                                    if let Some(previous) = lines.last_mut() {
+                                        previous.line_ending = LineEnding::NoNewline;
                                     }
-                                };
-
-                                lines.push(LineChange {
-                                    change_type,
-                                    content,
-                                    old_line_no: old_no,
-                                    new_line_no: new_no,
-                                });
-                            }
+                                    continue;
+                                }
+                                _ => {
+                                    let old_no = old_line_no;
+                                    let new_no = new_line_no;
+                                    old_line_no += 1;
+                                    new_line_no += 1;
+                                    (LineChangeType::Context, Some(old_no), Some(new_no))
+                                }
+                            };
+
+                            let encoding = match change_type {
+                                LineChangeType::Deletion => old_encoding,
+                                _ => new_encoding,
+                            };
+                            let decoded = encoding::decode(line.content(), encoding);
+                            let (content, line_ending) = LineEnding::split(&decoded);
+
+                            lines.push(LineChange {
+                                change_type,
+                                content: content.to_string(),
+                                line_ending,
+                                old_line_no: old_no,
+                                new_line_no: new_no,
+                            });
                         }
-
-                        hunks.push(DiffHunk {
-                            old_start: hunk.old_start() as usize,
-                            old_lines: hunk.old_lines() as usize,
-                            new_start: hunk.new_start() as usize,
-                            new_lines: hunk.new_lines() as usize,
-                            lines,
-                        });
                     }
+
+                    hunks.push(DiffHunk {
+                        old_start: hunk.old_start() as usize,
+                        old_lines: hunk.old_lines() as usize,
+                        new_start: hunk.new_start() as usize,
+                        new_lines: hunk.new_lines() as usize,
+                        lines,
+                    });
                 }
             }
         }
 
-        Ok((hunks, diff_text))
+        (hunks, diff_text)
     }
 
     /// Get file content from the current index
-    fn get_index_content(&self, path: Option<&std::path::Path>) -> Option<String> {
+    fn get_index_content(&self, path: Option<&std::path::Path>) -> Option<DecodedText> {
         let path = path?;
         let index = self.repo.index().ok()?;
         self.get_index_content_from(&index, Some(path))
@@ -1052,40 +1890,46 @@ impl GitRepository {
         &self,
         index: &git2::Index,
         path: Option<&std::path::Path>,
-    ) -> Option<String> {
+    ) -> Option<DecodedText> {
         let path = path?;
         let entry = index.get_path(path, 0)?;
         let blob = self.repo.find_blob(entry.id).ok()?;
-
-        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
-            Some(String::from_utf8_lossy(blob.content()).to_string())
-        } else {
-            None
-        }
+        decode_blob(&self.repo, &blob, path)
     }
 
     /// Get file content from working directory.
     ///
-    /// Returns `None` if:
+    /// Unlike blobs, working tree files are in their `working-tree-encoding`
+    /// if one is declared. Returns `None` if:
     /// - Path is not provided
     /// - Repository is bare (no working directory)
-    /// - File cannot be read (missing, permissions, binary/non-UTF8)
+    /// - File cannot be read (missing, permissions) or looks binary
     /// - File size exceeds MAX_BLOB_SIZE (500KB)
-    fn get_workdir_content(&self, path: Option<&std::path::Path>) -> Option<String> {
+    fn get_workdir_content(&self, path: Option<&std::path::Path>) -> Option<DecodedText> {
         let path = path?;
         let workdir = self.repo.workdir()?;
         let full_path = workdir.join(path);
 
-        match std::fs::read_to_string(&full_path) {
-            Ok(content) if content.len() <= MAX_BLOB_SIZE => Some(content),
-            _ => None,
+        let bytes = std::fs::read(&full_path).ok()?;
+        if bytes.len() > MAX_BLOB_SIZE {
+            return None;
         }
+
+        let declared = working_tree_encoding(&self.repo, path);
+        if declared.is_none() && looks_binary(&bytes) {
+            return None;
+        }
+
+        let encoding = encoding::detect(&bytes, declared);
+        Some((encoding::decode(&bytes, encoding), encoding))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::animation::HunkOrder;
+    use crate::test_support::TestRepo;
 
     #[test]
     fn test_should_exclude_lock_files() {
@@ -1213,50 +2057,6 @@ mod tests {
         assert_eq!(mode, DiffMode::Staged);
     }
 
-    // RAII guard for temporary git repository - auto-cleans on drop
-    struct TestRepo {
-        path: std::path::PathBuf,
-        repo: git2::Repository,
-    }
-
-    impl Drop for TestRepo {
-        fn drop(&mut self) {
-            let _ = std::fs::remove_dir_all(&self.path);
-        }
-    }
-
-    impl TestRepo {
-        fn new() -> Self {
-            use std::sync::atomic::{AtomicU64, Ordering};
-            use std::time::{SystemTime, UNIX_EPOCH};
-            static COUNTER: AtomicU64 = AtomicU64::new(0);
-
-            let unique_id = format!(
-                "{}_{}_{}",
-                std::process::id(),
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos(),
-                COUNTER.fetch_add(1, Ordering::SeqCst)
-            );
-            let path = std::env::temp_dir().join(format!("gitlogue_test_{}", unique_id));
-            if path.exists() {
-                std::fs::remove_dir_all(&path).unwrap();
-            }
-            std::fs::create_dir_all(&path).unwrap();
-
-            let repo = git2::Repository::init(&path).unwrap();
-
-            // Configure user for commits
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test User").unwrap();
-            config.set_str("user.email", "test@example.com").unwrap();
-
-            Self { path, repo }
-        }
-    }
-
     #[test]
     fn test_working_tree_diff_empty_repo() {
         let test_repo = TestRepo::new();
@@ -1460,4 +2260,494 @@ mod tests {
         let diff = now.signed_duration_since(result.date);
         assert!(diff.num_seconds() < 60);
     }
+
+    #[test]
+    fn test_shuffle_plays_every_commit_before_repeating() {
+        let test_repo = TestRepo::new();
+        let all: HashSet<String> = (0..5)
+            .map(|i| {
+                test_repo
+                    .commit_file(&format!("file{}.txt", i), "content\n")
+                    .to_string()
+            })
+            .collect();
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let first_cycle: HashSet<String> = (0..5)
+            .map(|_| repo.next_shuffle_commit().unwrap().hash)
+            .collect();
+        assert_eq!(first_cycle, all);
+
+        // The next permutation starts with a commit not shown at the end of the last one
+        let last_shown = repo.recent.borrow().back().unwrap().to_string();
+        assert_ne!(repo.next_shuffle_commit().unwrap().hash, last_shown);
+    }
+
+    #[test]
+    fn test_shuffle_resumes_from_state_store() {
+        let test_repo = TestRepo::new();
+        for i in 0..6 {
+            test_repo.commit_file(&format!("file{}.txt", i), "content\n");
+        }
+        let state_path = test_repo.path.join(".git").join("gitlogue-state.toml");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        let first_session: HashSet<String> = (0..3)
+            .map(|_| repo.next_shuffle_commit().unwrap().hash)
+            .collect();
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        let second_session: HashSet<String> = (0..3)
+            .map(|_| repo.next_shuffle_commit().unwrap().hash)
+            .collect();
+
+        assert!(first_session.is_disjoint(&second_session));
+    }
+
+    #[test]
+    fn test_random_commit_avoids_recently_shown() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("a.txt", "a\n");
+        test_repo.commit_file("b.txt", "b\n");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let mut previous = repo.random_commit().unwrap().hash;
+        for _ in 0..10 {
+            let current = repo.random_commit().unwrap().hash;
+            assert_ne!(current, previous);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn test_seeded_playback_is_reproducible() {
+        let test_repo = TestRepo::new();
+        for i in 0..8 {
+            test_repo.commit_file(
+                "main.rs",
+                (0..=i)
+                    .map(|j| format!("fn step_{}() {{}}\n", j))
+                    .collect::<String>(),
+            );
+        }
+
+        let play = || {
+            let mut repo = GitRepository::open(&test_repo.path).unwrap();
+            repo.set_rng(StdRng::seed_from_u64(7));
+            let commits: Vec<CommitMetadata> =
+                (0..5).map(|_| repo.random_commit().unwrap()).collect();
+
+            let mut engine = crate::animation::AnimationEngine::new(30);
+            engine.set_rng(StdRng::seed_from_u64(7));
+            engine.set_typing_settings(&crate::typing::TypingSettings::default());
+            engine.set_hunk_order(HunkOrder::Random);
+            engine.load_commit(&commits[4]);
+            let mut steps = Vec::new();
+            while let Some(step) = engine.advance() {
+                steps.push(format!("{:?}", step));
+            }
+
+            let hashes: Vec<String> = commits.into_iter().map(|c| c.hash).collect();
+            (hashes, steps)
+        };
+
+        let (hashes, steps) = play();
+        assert_eq!(play(), (hashes, steps));
+    }
+
+    #[test]
+    fn test_resume_continues_after_saved_cursor() {
+        let test_repo = TestRepo::new();
+        let oids: Vec<Oid> = (0..4)
+            .map(|i| test_repo.commit_file(&format!("file{}.txt", i), "content\n"))
+            .collect();
+        let state_path = test_repo.path.join(".git").join("gitlogue-state.toml");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        repo.next_asc_commit().unwrap();
+        repo.next_asc_commit().unwrap();
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        assert_eq!(
+            repo.resume(PlaybackDirection::Desc).unwrap(),
+            ResumeOutcome::NoCursor
+        );
+        assert_eq!(
+            repo.resume(PlaybackDirection::Asc).unwrap(),
+            ResumeOutcome::Resumed
+        );
+        assert_eq!(repo.next_asc_commit().unwrap().hash, oids[2].to_string());
+    }
+
+    #[test]
+    fn test_resume_with_relative_date_filter() {
+        let test_repo = TestRepo::new();
+        let oids: Vec<Oid> = (0..3)
+            .map(|i| test_repo.commit_file(&format!("file{}.txt", i), "content\n"))
+            .collect();
+        let state_path = test_repo.path.join(".git").join("gitlogue-state.toml");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        repo.set_after_filter(Some("10 years ago")).unwrap();
+        assert!(repo
+            .cursor_key(PlaybackDirection::Asc)
+            .ends_with(" after=10 years ago"));
+        repo.next_asc_commit().unwrap();
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_state_store(StateStore::at(&state_path));
+        repo.set_after_filter(Some("10 years ago")).unwrap();
+        assert_eq!(
+            repo.resume(PlaybackDirection::Asc).unwrap(),
+            ResumeOutcome::Resumed
+        );
+        assert_eq!(repo.next_asc_commit().unwrap().hash, oids[1].to_string());
+    }
+
+    #[test]
+    fn test_resume_handles_rewritten_cursor() {
+        let test_repo = TestRepo::new();
+        let oids: Vec<Oid> = (0..3)
+            .map(|i| test_repo.commit_file(&format!("file{}.txt", i), "content\n"))
+            .collect();
+        let state_path = test_repo.path.join(".git").join("gitlogue-state.toml");
+        let store = StateStore::at(&state_path);
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        let key = repo.state_key();
+        let asc_key = repo.cursor_key(PlaybackDirection::Asc);
+        let save = |time: i64| {
+            store
+                .update_repo(&key, |state| {
+                    state.cursors.insert(
+                        asc_key.clone(),
+                        PlaybackCursor {
+                            commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                            time,
+                        },
+                    );
+                })
+                .unwrap();
+        };
+
+        // Unknown commit authored before everything: continue from the oldest commit
+        save(0);
+        repo.set_state_store(store.clone());
+        assert_eq!(
+            repo.resume(PlaybackDirection::Asc).unwrap(),
+            ResumeOutcome::Rewritten
+        );
+        assert_eq!(repo.next_asc_commit().unwrap().hash, oids[0].to_string());
+
+        // Unknown commit authored after everything: start over
+        save(i64::MAX);
+        assert_eq!(
+            repo.resume(PlaybackDirection::Asc).unwrap(),
+            ResumeOutcome::Restarted
+        );
+        assert_eq!(repo.next_asc_commit().unwrap().hash, oids[0].to_string());
+    }
+
+    #[test]
+    fn test_line_endings_are_preserved() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("dos.txt", "one\r\ntwo");
+        let oid = test_repo.commit_file("dos.txt", "one\r\ntwo\r\nthree\r\n");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = &metadata.changes[0];
+        assert_eq!(
+            change.new_content.as_deref(),
+            Some("one\r\ntwo\r\nthree\r\n")
+        );
+
+        let lines: Vec<_> = change.hunks[0]
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.change_type.clone(),
+                    line.content.as_str(),
+                    line.line_ending,
+                )
+            })
+            .collect();
+        assert!(lines.contains(&(LineChangeType::Deletion, "two", LineEnding::NoNewline)));
+        assert!(lines.contains(&(LineChangeType::Addition, "two", LineEnding::CrLf)));
+        assert!(lines.contains(&(LineChangeType::Addition, "three", LineEnding::CrLf)));
+    }
+
+    #[test]
+    fn test_latin1_content_is_decoded() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("notes.txt", b"caf\xe9\n".as_slice());
+        let oid = test_repo.commit_file("notes.txt", b"caf\xe9\nna\xefve\n".as_slice());
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = &metadata.changes[0];
+        assert!(!change.is_binary);
+        assert_eq!(change.new_content.as_deref(), Some("café\nnaïve\n"));
+
+        let added: Vec<_> = change.hunks[0]
+            .lines
+            .iter()
+            .filter(|line| matches!(line.change_type, LineChangeType::Addition))
+            .map(|line| line.content.as_str())
+            .collect();
+        assert_eq!(added, vec!["naïve"]);
+    }
+
+    #[test]
+    fn test_replay_reproduces_committed_content() {
+        let test_repo = TestRepo::new();
+        let original: String = (1..=40).map(|i| format!("line {}\n", i)).collect();
+        test_repo.commit_file("main.txt", &original);
+        test_repo.commit_file("dos.txt", "a\r\nb\r\nc");
+
+        let edited = original
+            .replace("line 2\n", "")
+            .replace("line 20\n", "line 20\nnew 20a\nnew 20b\n")
+            .replace("line 38\n", "changed 38\n");
+        test_repo.commit_file("main.txt", edited);
+        test_repo.commit_file("dos.txt", "a\r\nB\r\nc\r\nd");
+        let oid = test_repo.commit_file("new.txt", "first\nsecond");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        for hash in repo.commit_hashes().unwrap() {
+            let metadata = repo.get_commit(&hash).unwrap();
+            assert_eq!(
+                crate::verify::verify_commit(&metadata, HunkOrder::default()),
+                Vec::new()
+            );
+        }
+        assert_eq!(repo.commit_hashes().unwrap().last(), Some(&oid.to_string()));
+    }
+
+    #[test]
+    fn test_hunk_orders_reproduce_committed_content() {
+        let test_repo = TestRepo::new();
+        let original: String = (1..=60).map(|i| format!("line {}\n", i)).collect();
+        test_repo.commit_file("lib.rs", &original);
+        test_repo.commit_file("main.rs", &original);
+
+        let lib = original
+            .replace("line 1\n", "use std::fmt;\nline 1\n")
+            .replace("line 20\n", "")
+            .replace("line 40\n", "line 40\nnew 40a\nnew 40b\n")
+            .replace("line 58\n", "fn changed() {}\n");
+        test_repo.commit_file("lib.rs", lib);
+        let main = original
+            .replace("line 10\n", "ten\n")
+            .replace("line 50\n", "line 50\nfifty\n");
+        let oid = test_repo.commit_file("main.rs", main);
+
+        // Commit both files at once so that their hunks can be interleaved
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let mut metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let parent = repo.repo.find_commit(oid).unwrap().parent_id(0).unwrap();
+        metadata
+            .changes
+            .extend(repo.get_commit(&parent.to_string()).unwrap().changes);
+
+        for order in HunkOrder::value_variants() {
+            assert_eq!(
+                crate::verify::verify_commit(&metadata, *order),
+                Vec::new(),
+                "{:?}",
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_context_replay_reproduces_committed_content() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("main.txt", "a\nb\nc\nd\ne\n");
+        let oid = test_repo.commit_file("main.txt", "a\nnew\nb\nc\nD\ne\nf\n");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_diff_settings(DiffSettings {
+            context_lines: 0,
+            algorithm: DiffAlgorithm::Patience,
+            ..DiffSettings::default()
+        });
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        assert!(metadata.changes[0].hunks.iter().all(|hunk| hunk
+            .lines
+            .iter()
+            .all(|line| line.change_type != LineChangeType::Context)));
+        assert_eq!(
+            crate::verify::verify_commit(&metadata, HunkOrder::default()),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn test_whitespace_only_commits() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("main.rs", "fn main() {\n  run();\n}\n");
+        let reformat = test_repo.commit_file("main.rs", "fn main() {\n    run();\n}\n\n");
+        let edit = test_repo.commit_file("main.rs", "fn main() {\n    start();\n}\n\n");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        assert!(
+            !repo
+                .get_commit(&reformat.to_string())
+                .unwrap()
+                .whitespace_only
+        );
+
+        repo.set_diff_settings(DiffSettings {
+            whitespace_commits: WhitespaceCommits::Compact,
+            ..DiffSettings::default()
+        });
+        let metadata = repo.get_commit(&reformat.to_string()).unwrap();
+        assert!(metadata.whitespace_only);
+        assert_eq!(
+            metadata.changes[0].exclusion_reason.as_deref(),
+            Some("whitespace only")
+        );
+        assert!(!repo.get_commit(&edit.to_string()).unwrap().whitespace_only);
+
+        repo.set_diff_settings(DiffSettings {
+            whitespace_commits: WhitespaceCommits::Skip,
+            ..DiffSettings::default()
+        });
+        repo.set_commit_range(&format!("{}~1..", reformat)).unwrap();
+        let played = repo
+            .next_playable(|repo| repo.next_range_commit_asc())
+            .unwrap();
+        assert_eq!(played.hash, edit.to_string());
+    }
+
+    #[test]
+    fn test_commit_count() {
+        let test_repo = TestRepo::new();
+        let first = test_repo.commit_file("a.txt", "a\n");
+        test_repo.commit_file("a.txt", "a\nb\n");
+        test_repo.commit_file("b.txt", "b\n");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        assert_eq!(repo.commit_count().unwrap(), 3);
+        repo.set_commit_range(&format!("{}..", first)).unwrap();
+        assert_eq!(repo.commit_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_lock_file_dependency_changes() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file(
+            "Cargo.lock",
+            "[[package]]\nname = \"serde\"\nversion = \"1.0.190\"\n",
+        );
+        let oid = test_repo.commit_file(
+            "Cargo.lock",
+            "[[package]]\nname = \"serde\"\nversion = \"1.0.197\"\n",
+        );
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = &metadata.changes[0];
+        assert!(change.is_excluded);
+        assert_eq!(
+            change.dependency_changes,
+            vec![DependencyChange::Updated {
+                name: "serde".to_string(),
+                from: "1.0.190".to_string(),
+                to: "1.0.197".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_binary_changes_are_previewed() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("data.bin", b"\x00\x01header-v1\x00".as_slice());
+        let oid = test_repo.commit_file("data.bin", b"\x00\x01header-v2\x00\xff".as_slice());
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8"/>"#;
+        let svg_oid = test_repo.commit_file("icon.svg", svg);
+        let text_oid = test_repo.commit_file("notes.txt", "not binary\n");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let binary = metadata.changes[0].binary.as_ref().unwrap();
+        assert!(!binary.is_image());
+        assert_eq!(binary.size_change(), "12 B → 13 B (+1 B)");
+        assert_eq!(binary.strings, vec!["header-v2"]);
+
+        let metadata = repo.get_commit(&svg_oid.to_string()).unwrap();
+        let binary = metadata.changes[0].binary.as_ref().unwrap();
+        assert_eq!(binary.dimension_change().as_deref(), Some("8×8"));
+
+        let metadata = repo.get_commit(&text_oid.to_string()).unwrap();
+        assert!(metadata.changes[0].binary.is_none());
+    }
+
+    #[test]
+    fn test_submodule_pointer_change() {
+        let test_repo = TestRepo::new();
+        let submodule = TestRepo::init(test_repo.path.join("lib"));
+        let first = submodule.commit_file("a.txt", "a\n");
+        submodule.commit_file("b.txt", "b\n");
+        let last = submodule.commit_file("c.txt", "c\n");
+
+        test_repo.commit_gitlink("lib", first);
+        let oid = test_repo.commit_gitlink("lib", last);
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = metadata.changes[0].submodule.as_ref().unwrap();
+        let summaries: Vec<&str> = change
+            .commits
+            .iter()
+            .flatten()
+            .map(|commit| commit.summary.as_str())
+            .collect();
+        assert_eq!(summaries, vec!["c.txt", "b.txt"]);
+        assert!(change.replays.is_empty());
+
+        repo.set_submodule_replay(true);
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = metadata.changes[0].submodule.as_ref().unwrap();
+        let replayed: Vec<&str> = change
+            .replays
+            .iter()
+            .map(|replay| replay.changes[0].path.as_str())
+            .collect();
+        assert_eq!(replayed, vec!["b.txt", "c.txt"]);
+        assert!(crate::verify::verify_commit(&metadata, HunkOrder::default()).is_empty());
+    }
+
+    #[test]
+    fn test_notebook_is_diffed_as_cells() {
+        let notebook = |source: &str, output: &str| {
+            format!(
+                r#"{{"cells": [{{"cell_type": "code", "metadata": {{}}, "source": "{}",
+                "outputs": [{{"output_type": "stream", "name": "stdout", "text": "{}"}}]}}],
+                "metadata": {{"kernelspec": {{"language": "python"}}}}, "nbformat": 4}}"#,
+                source, output
+            )
+        };
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("analysis.ipynb", notebook("x = 1", "1"));
+        let oid = test_repo.commit_file("analysis.ipynb", notebook("x = 2", "2"));
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        let change = &metadata.changes[0];
+        assert_eq!(change.old_content.as_deref(), Some("# %%\nx = 1\n"));
+        assert_eq!(change.new_content.as_deref(), Some("# %%\nx = 2\n"));
+        assert_eq!(change.syntax_path.as_deref(), Some("analysis.ipynb.py"));
+        assert_eq!(
+            crate::verify::verify_commit(&metadata, HunkOrder::default()),
+            Vec::new()
+        );
+    }
 }
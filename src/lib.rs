@@ -1,3 +1,19 @@
+pub mod animation;
+pub mod atomic_file;
+pub mod bookmarks;
+pub mod clock;
+pub mod completion;
+pub mod encoding;
 pub mod git;
+pub mod lockfile;
+pub mod playlist;
+pub mod preview;
+pub mod state;
+pub mod storyboard;
 pub mod syntax;
+#[cfg(test)]
+mod test_support;
 pub mod theme;
+pub mod transform;
+pub mod typing;
+pub mod verify;
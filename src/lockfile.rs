@@ -0,0 +1,715 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde_json::Value;
+
+/// Resolved package versions by package name
+type Packages = BTreeMap<String, BTreeSet<String>>;
+
+/// A dependency added, removed or changed by a lock file update
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencyChange {
+    Added {
+        name: String,
+        version: String,
+    },
+    Removed {
+        name: String,
+    },
+    Updated {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for DependencyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyChange::Added { name, version } => write!(f, "+ {} {}", name, version),
+            DependencyChange::Removed { name } => write!(f, "- {}", name),
+            DependencyChange::Updated { name, from, to } => {
+                let arrow = if compare_versions(to, from) == Ordering::Less {
+                    "⬇"
+                } else {
+                    "⬆"
+                };
+                write!(f, "{} {} {} → {}", arrow, name, from, to)
+            }
+        }
+    }
+}
+
+/// Compare the package lists of two versions of a lock file.
+///
+/// Returns `None` if the lock file format is not recognised or either side
+/// cannot be parsed. A missing side (added or deleted lock file) is passed as
+/// an empty string.
+pub fn diff(path: &str, old: &str, new: &str) -> Option<Vec<DependencyChange>> {
+    let parser = parser(path)?;
+    let old = parse(parser, old)?;
+    let new = parse(parser, new)?;
+
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let changes = names
+        .into_iter()
+        .filter_map(|name| match (old.get(name), new.get(name)) {
+            (None, Some(versions)) => Some(DependencyChange::Added {
+                name: name.clone(),
+                version: join(versions.iter()),
+            }),
+            (Some(_), None) => Some(DependencyChange::Removed { name: name.clone() }),
+            (Some(from), Some(to)) if from != to => {
+                // Only mention the versions that changed when several are locked
+                let removed: Vec<&String> = from.difference(to).collect();
+                let added: Vec<&String> = to.difference(from).collect();
+                let (from, to) = if removed.is_empty() || added.is_empty() {
+                    (join(from.iter()), join(to.iter()))
+                } else {
+                    (join(removed.into_iter()), join(added.into_iter()))
+                };
+                Some(DependencyChange::Updated {
+                    name: name.clone(),
+                    from,
+                    to,
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    Some(changes)
+}
+
+fn join<'a>(versions: impl Iterator<Item = &'a String>) -> String {
+    versions.map(String::as_str).collect::<Vec<_>>().join(", ")
+}
+
+// Compare dotted version numbers component by component, numerically where possible
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches(['v', '='])
+            .split(|c: char| !c.is_ascii_digit())
+            .take_while(|part| !part.is_empty())
+            .filter_map(|part| part.parse().ok())
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// Whether `diff` knows the lock file format of `path`, from its file name.
+pub fn is_supported(path: &str) -> bool {
+    parser(path).is_some()
+}
+
+fn parser(path: &str) -> Option<fn(&str) -> Option<Packages>> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let parser: fn(&str) -> Option<Packages> = match file_name {
+        "Cargo.lock" | "poetry.lock" | "uv.lock" => parse_toml_packages,
+        "package-lock.json" => parse_package_lock,
+        "yarn.lock" => |content| Some(parse_yarn_lock(content)),
+        "pnpm-lock.yaml" => |content| Some(parse_pnpm_lock(content)),
+        "bun.lock" => |content| Some(parse_bun_lock(content)),
+        "Gemfile.lock" => |content| Some(parse_gemfile_lock(content)),
+        "Pipfile.lock" => parse_pipfile_lock,
+        "composer.lock" => parse_composer_lock,
+        "go.sum" => |content| Some(parse_go_sum(content)),
+        "Package.resolved" => parse_package_resolved,
+        "pubspec.lock" => |content| Some(parse_pubspec_lock(content)),
+        "packages.lock.json" => parse_nuget_lock,
+        "project.assets.json" => parse_project_assets,
+        "mix.lock" => |content| Some(parse_mix_lock(content)),
+        "gradle.lockfile" | "buildscript-gradle.lockfile" => {
+            |content| Some(parse_gradle_lockfile(content))
+        }
+        "build.sbt.lock" => parse_sbt_lock,
+        _ => return None,
+    };
+    Some(parser)
+}
+
+fn parse(parser: fn(&str) -> Option<Packages>, content: &str) -> Option<Packages> {
+    if content.trim().is_empty() {
+        return Some(Packages::new());
+    }
+    parser(content)
+}
+
+fn insert(packages: &mut Packages, name: &str, version: &str) {
+    if !name.is_empty() && !version.is_empty() {
+        packages
+            .entry(name.to_string())
+            .or_default()
+            .insert(version.to_string());
+    }
+}
+
+// Split "name@version", keeping the leading "@" of scoped npm packages
+fn split_at_version(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.rfind('@')? + 1;
+    Some((&spec[..at], &spec[at + 1..]))
+}
+
+// [[package]] tables with name and version (Cargo, Poetry, uv)
+fn parse_toml_packages(content: &str) -> Option<Packages> {
+    let table: toml::Table = content.parse().ok()?;
+    let mut packages = Packages::new();
+    for package in table.get("package")?.as_array()? {
+        let name = package.get("name").and_then(|v| v.as_str());
+        let version = package.get("version").and_then(|v| v.as_str());
+        if let (Some(name), Some(version)) = (name, version) {
+            insert(&mut packages, name, version);
+        }
+    }
+    Some(packages)
+}
+
+fn parse_package_lock(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    if let Some(entries) = json.get("packages").and_then(Value::as_object) {
+        // lockfileVersion 2 and 3: keyed by install path
+        for (path, entry) in entries {
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                insert(&mut packages, name, version);
+            }
+        }
+    } else if let Some(entries) = json.get("dependencies").and_then(Value::as_object) {
+        // lockfileVersion 1: nested dependency tree
+        fn walk(entries: &serde_json::Map<String, Value>, packages: &mut Packages) {
+            for (name, entry) in entries {
+                if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                    insert(packages, name, version);
+                }
+                if let Some(nested) = entry.get("dependencies").and_then(Value::as_object) {
+                    walk(nested, packages);
+                }
+            }
+        }
+        walk(entries, &mut packages);
+    }
+
+    Some(packages)
+}
+
+fn parse_yarn_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+    let mut name: Option<String> = None;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && line.ends_with(':') && !line.starts_with('#') {
+            // `"lodash@^4.17.0", lodash@^4.17.21:` or `"lodash@npm:^4.17.21":`
+            let spec = line
+                .trim_end_matches(':')
+                .split(',')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches('"');
+            name = split_at_version(spec).map(|(name, _)| name.to_string());
+        } else if let Some(version) = line
+            .strip_prefix("  version ")
+            .or_else(|| line.strip_prefix("  version: "))
+        {
+            if let Some(name) = &name {
+                insert(&mut packages, name, version.trim().trim_matches('"'));
+            }
+        }
+    }
+
+    packages
+}
+
+fn parse_pnpm_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            in_packages = line == "packages:";
+            continue;
+        }
+        if !in_packages || line.starts_with("    ") || !line.ends_with(':') {
+            continue;
+        }
+
+        // `  /lodash@4.17.21:`, `  lodash@4.17.21:` or `  /lodash/4.17.21:` (v5)
+        let key = line.trim().trim_end_matches(':').trim_matches(['\'', '"']);
+        let key = key.split('(').next().unwrap_or(key);
+        let stripped = key.trim_start_matches('/');
+        let spec =
+            if key.starts_with('/') && stripped.get(1..).is_some_and(|rest| !rest.contains('@')) {
+                stripped.rsplit_once('/')
+            } else {
+                split_at_version(stripped)
+            };
+        if let Some((name, version)) = spec {
+            insert(&mut packages, name, version);
+        }
+    }
+
+    packages
+}
+
+fn parse_bun_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("\"packages\"") {
+            in_packages = true;
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+
+        // `    "lodash": ["lodash@4.17.21", "", {}, "sha512-..."],`
+        if let Some((_, rest)) = trimmed.split_once(": [\"") {
+            if let Some((name, version)) = rest.split('"').next().and_then(split_at_version) {
+                insert(&mut packages, name, version);
+            }
+        }
+    }
+
+    packages
+}
+
+fn parse_gemfile_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+
+    for line in content.lines() {
+        // Resolved gems are indented by exactly four spaces under `specs:`
+        let Some(spec) = line.strip_prefix("    ") else {
+            continue;
+        };
+        if spec.starts_with(' ') {
+            continue;
+        }
+        if let Some((name, version)) = spec.split_once(" (") {
+            let version = version.trim_end_matches(')');
+            let version = version.split('-').next().unwrap_or(version);
+            insert(&mut packages, name, version);
+        }
+    }
+
+    packages
+}
+
+fn parse_pipfile_lock(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    for section in ["default", "develop"] {
+        let Some(entries) = json.get(section).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, entry) in entries {
+            if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                insert(&mut packages, name, version.trim_start_matches("=="));
+            }
+        }
+    }
+
+    Some(packages)
+}
+
+fn parse_composer_lock(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    for section in ["packages", "packages-dev"] {
+        for entry in json
+            .get(section)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = entry.get("name").and_then(Value::as_str);
+            let version = entry.get("version").and_then(Value::as_str);
+            if let (Some(name), Some(version)) = (name, version) {
+                insert(&mut packages, name, version);
+            }
+        }
+    }
+
+    Some(packages)
+}
+
+fn parse_go_sum(content: &str) -> Packages {
+    let mut packages = Packages::new();
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        if let (Some(module), Some(version)) = (fields.next(), fields.next()) {
+            insert(&mut packages, module, version.trim_end_matches("/go.mod"));
+        }
+    }
+
+    packages
+}
+
+fn parse_package_resolved(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    // Version 1 nests pins under "object" and names them "package"
+    let pins = json
+        .get("pins")
+        .or_else(|| json.get("object").and_then(|object| object.get("pins")))
+        .and_then(Value::as_array);
+    for pin in pins.into_iter().flatten() {
+        let name = pin
+            .get("identity")
+            .or_else(|| pin.get("package"))
+            .and_then(Value::as_str);
+        let state = pin.get("state");
+        let version = state
+            .and_then(|state| state.get("version"))
+            .and_then(Value::as_str)
+            .or_else(|| {
+                state
+                    .and_then(|state| state.get("revision"))
+                    .and_then(Value::as_str)
+                    .map(|revision| &revision[..revision.len().min(7)])
+            });
+        if let (Some(name), Some(version)) = (name, version) {
+            insert(&mut packages, name, version);
+        }
+    }
+
+    Some(packages)
+}
+
+fn parse_pubspec_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+    let mut in_packages = false;
+    let mut name: Option<&str> = None;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            in_packages = line == "packages:";
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+
+        if let Some(key) = line.strip_prefix("  ").filter(|key| !key.starts_with(' ')) {
+            name = Some(key.trim_end_matches(':').trim_matches('"'));
+        } else if let Some(version) = line.strip_prefix("    version: ") {
+            if let Some(name) = name {
+                insert(&mut packages, name, version.trim_matches('"'));
+            }
+        }
+    }
+
+    packages
+}
+
+fn parse_nuget_lock(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    // Dependencies are grouped by target framework
+    let frameworks = json.get("dependencies").and_then(Value::as_object)?;
+    for entries in frameworks.values().filter_map(Value::as_object) {
+        for (name, entry) in entries {
+            if let Some(version) = entry.get("resolved").and_then(Value::as_str) {
+                insert(&mut packages, name, version);
+            }
+        }
+    }
+
+    Some(packages)
+}
+
+fn parse_project_assets(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    for key in json.get("libraries").and_then(Value::as_object)?.keys() {
+        if let Some((name, version)) = key.split_once('/') {
+            insert(&mut packages, name, version);
+        }
+    }
+
+    Some(packages)
+}
+
+fn parse_mix_lock(content: &str) -> Packages {
+    let mut packages = Packages::new();
+
+    for line in content.lines() {
+        // `  "jason": {:hex, :jason, "1.4.1", "af1504e3...", ...},`
+        let Some((name, rest)) = line.trim().split_once("\": {") else {
+            continue;
+        };
+        let name = name.trim_start_matches('"');
+        let version = if rest.starts_with(":hex") {
+            rest.split('"').nth(1)
+        } else {
+            // Git dependencies are locked to a revision
+            rest.split('"')
+                .nth(3)
+                .map(|revision| &revision[..revision.len().min(7)])
+        };
+        if let Some(version) = version {
+            insert(&mut packages, name, version);
+        }
+    }
+
+    packages
+}
+
+fn parse_gradle_lockfile(content: &str) -> Packages {
+    let mut packages = Packages::new();
+
+    for line in content.lines().filter(|line| !line.starts_with('#')) {
+        // `com.google.guava:guava:32.1.2-jre=compileClasspath,runtimeClasspath`
+        let coordinates = line.split('=').next().unwrap_or(line);
+        if let Some((name, version)) = coordinates.rsplit_once(':') {
+            insert(&mut packages, name, version);
+        }
+    }
+
+    packages
+}
+
+fn parse_sbt_lock(content: &str) -> Option<Packages> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut packages = Packages::new();
+
+    for entry in json.get("dependencies").and_then(Value::as_array)? {
+        let org = entry.get("org").and_then(Value::as_str);
+        let name = entry.get("name").and_then(Value::as_str);
+        let version = entry.get("version").and_then(Value::as_str);
+        if let (Some(org), Some(name), Some(version)) = (org, name, version) {
+            insert(&mut packages, &format!("{}:{}", org, name), version);
+        }
+    }
+
+    Some(packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(path: &str, old: &str, new: &str) -> Vec<String> {
+        diff(path, old, new)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_lock() {
+        let old = r#"
+[[package]]
+name = "openssl"
+version = "0.10.64"
+
+[[package]]
+name = "serde"
+version = "1.0.190"
+"#;
+        let new = r#"
+[[package]]
+name = "serde"
+version = "1.0.197"
+
+[[package]]
+name = "tokio"
+version = "1.37.0"
+"#;
+        assert_eq!(
+            lines("Cargo.lock", old, new),
+            vec!["- openssl", "⬆ serde 1.0.190 → 1.0.197", "+ tokio 1.37.0"]
+        );
+    }
+
+    #[test]
+    fn test_package_lock_scoped_packages() {
+        let old = r#"{"packages": {"": {"name": "app"}, "node_modules/@types/node": {"version": "20.1.0"}}}"#;
+        let new = r#"{"packages": {"": {"name": "app"}, "node_modules/@types/node": {"version": "18.0.0"}}}"#;
+        assert_eq!(
+            lines("web/package-lock.json", old, new),
+            vec!["⬇ @types/node 20.1.0 → 18.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_go_sum_added_file() {
+        let new = "golang.org/x/text v0.14.0 h1:abc=\ngolang.org/x/text v0.14.0/go.mod h1:def=\n";
+        assert_eq!(
+            lines("go.sum", "", new),
+            vec!["+ golang.org/x/text v0.14.0"]
+        );
+    }
+
+    #[test]
+    fn test_yarn_lock() {
+        let old = "\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n  version \"7.1.0\"\n";
+        let new = "\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n  version \"7.2.0\"\n";
+        assert_eq!(
+            lines("yarn.lock", old, new),
+            vec!["⬆ @babel/core 7.1.0 → 7.2.0"]
+        );
+    }
+
+    #[test]
+    fn test_poetry_lock() {
+        let old = "[[package]]\nname = \"requests\"\nversion = \"2.31.0\"\n";
+        let new = "[[package]]\nname = \"requests\"\nversion = \"2.32.3\"\n\n[[package]]\nname = \"idna\"\nversion = \"3.7\"\n";
+        assert_eq!(
+            lines("poetry.lock", old, new),
+            vec!["+ idna 3.7", "⬆ requests 2.31.0 → 2.32.3"]
+        );
+    }
+
+    #[test]
+    fn test_pnpm_lock() {
+        // Several versions of a package, peer suffixes and the v5 key format
+        let old = "lockfileVersion: '6.0'\n\npackages:\n\n  /lodash@3.10.1:\n    resolution: {integrity: sha512-a}\n\n  /lodash@4.17.20:\n    resolution: {integrity: sha512-b}\n\n  /@types/node/20.1.0:\n    dev: true\n";
+        let new = "lockfileVersion: '9.0'\n\npackages:\n\n  lodash@3.10.1:\n    resolution: {integrity: sha512-a}\n\n  lodash@4.17.21:\n    resolution: {integrity: sha512-c}\n\n  '@types/node@20.2.0':\n    resolution: {integrity: sha512-d}\n\n  react-dom@18.2.0(react@18.2.0):\n    dev: false\n";
+        assert_eq!(
+            lines("pnpm-lock.yaml", old, new),
+            vec![
+                "⬆ @types/node 20.1.0 → 20.2.0",
+                "⬆ lodash 4.17.20 → 4.17.21",
+                "+ react-dom 18.2.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bun_lock() {
+        let old = "{\n  \"lockfileVersion\": 1,\n  \"packages\": {\n    \"zod\": [\"zod@3.22.4\", \"\", {}, \"sha512-a\"],\n  }\n}\n";
+        let new = "{\n  \"lockfileVersion\": 1,\n  \"packages\": {\n    \"@hono/node-server\": [\"@hono/node-server@1.8.0\", \"\", {}, \"sha512-b\"],\n  }\n}\n";
+        assert_eq!(
+            lines("bun.lock", old, new),
+            vec!["+ @hono/node-server 1.8.0", "- zod"]
+        );
+    }
+
+    #[test]
+    fn test_gemfile_lock() {
+        let old = "GEM\n  remote: https://rubygems.org/\n  specs:\n    nokogiri (1.15.0-x86_64-linux)\n      racc (~> 1.4)\n    racc (1.7.1)\n\nDEPENDENCIES\n  nokogiri\n";
+        let new = "GEM\n  remote: https://rubygems.org/\n  specs:\n    nokogiri (1.16.2-x86_64-linux)\n      racc (~> 1.4)\n    racc (1.7.1)\n\nDEPENDENCIES\n  nokogiri\n";
+        assert_eq!(
+            lines("Gemfile.lock", old, new),
+            vec!["⬆ nokogiri 1.15.0 → 1.16.2"]
+        );
+    }
+
+    #[test]
+    fn test_pipfile_lock() {
+        let old = r#"{"default": {"flask": {"version": "==3.0.0"}}, "develop": {}}"#;
+        let new = r#"{"default": {"flask": {"version": "==2.3.3"}}, "develop": {"pytest": {"version": "==8.0.0"}}}"#;
+        assert_eq!(
+            lines("Pipfile.lock", old, new),
+            vec!["⬇ flask 3.0.0 → 2.3.3", "+ pytest 8.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_composer_lock() {
+        let old = r#"{"packages": [{"name": "monolog/monolog", "version": "3.5.0"}], "packages-dev": [{"name": "phpunit/phpunit", "version": "10.5.0"}]}"#;
+        let new = r#"{"packages": [{"name": "monolog/monolog", "version": "3.6.0"}], "packages-dev": []}"#;
+        assert_eq!(
+            lines("composer.lock", old, new),
+            vec!["⬆ monolog/monolog 3.5.0 → 3.6.0", "- phpunit/phpunit"]
+        );
+    }
+
+    #[test]
+    fn test_package_resolved() {
+        // Version 1 nests pins under "object"; branch pins only have a revision
+        let old = r#"{"object": {"pins": [{"package": "swift-nio", "state": {"version": "2.60.0"}}]}, "version": 1}"#;
+        let new = r#"{"pins": [{"identity": "swift-nio", "state": {"version": "2.62.0"}}, {"identity": "swift-log", "state": {"branch": "main", "revision": "abcdef123456"}}], "version": 2}"#;
+        assert_eq!(
+            lines(
+                "App.xcodeproj/project.xcworkspace/xcshareddata/swiftpm/Package.resolved",
+                old,
+                new
+            ),
+            vec!["+ swift-log abcdef1", "⬆ swift-nio 2.60.0 → 2.62.0"]
+        );
+    }
+
+    #[test]
+    fn test_pubspec_lock() {
+        let old = "packages:\n  http:\n    dependency: \"direct main\"\n    source: hosted\n    version: \"1.1.0\"\nsdks:\n  dart: \">=3.0.0 <4.0.0\"\n";
+        let new = "packages:\n  http:\n    dependency: \"direct main\"\n    source: hosted\n    version: \"1.2.1\"\nsdks:\n  dart: \">=3.0.0 <4.0.0\"\n";
+        assert_eq!(
+            lines("pubspec.lock", old, new),
+            vec!["⬆ http 1.1.0 → 1.2.1"]
+        );
+    }
+
+    #[test]
+    fn test_nuget_lock() {
+        let old = r#"{"version": 1, "dependencies": {"net8.0": {"Newtonsoft.Json": {"type": "Direct", "resolved": "13.0.1"}}}}"#;
+        let new = r#"{"version": 1, "dependencies": {"net8.0": {"Newtonsoft.Json": {"type": "Direct", "resolved": "13.0.3"}}, "net6.0": {"Serilog": {"type": "Direct", "resolved": "3.1.1"}}}}"#;
+        assert_eq!(
+            lines("src/App/packages.lock.json", old, new),
+            vec!["⬆ Newtonsoft.Json 13.0.1 → 13.0.3", "+ Serilog 3.1.1"]
+        );
+    }
+
+    #[test]
+    fn test_project_assets() {
+        let old = r#"{"version": 3, "libraries": {"Serilog/3.1.1": {"type": "package"}}}"#;
+        let new = r#"{"version": 3, "libraries": {"Serilog/2.12.0": {"type": "package"}, "Polly/8.2.0": {"type": "package"}}}"#;
+        assert_eq!(
+            lines("obj/project.assets.json", old, new),
+            vec!["+ Polly 8.2.0", "⬇ Serilog 3.1.1 → 2.12.0"]
+        );
+    }
+
+    #[test]
+    fn test_mix_lock() {
+        let old = "%{\n  \"jason\": {:hex, :jason, \"1.4.1\", \"af1504e3\", [:mix], [], \"hexpm\", \"abc\"},\n}\n";
+        let new = "%{\n  \"jason\": {:hex, :jason, \"1.4.3\", \"9a90e868\", [:mix], [], \"hexpm\", \"def\"},\n  \"plug\": {:git, \"https://github.com/elixir-plug/plug.git\", \"0123456789abcdef\", [branch: \"main\"]},\n}\n";
+        assert_eq!(
+            lines("mix.lock", old, new),
+            vec!["⬆ jason 1.4.1 → 1.4.3", "+ plug 0123456"]
+        );
+    }
+
+    #[test]
+    fn test_gradle_lockfile() {
+        let old = "# This is a Gradle generated file for dependency locking.\ncom.google.guava:guava:32.1.2-jre=compileClasspath,runtimeClasspath\nempty=annotationProcessor\n";
+        let new = "# This is a Gradle generated file for dependency locking.\ncom.google.guava:guava:33.0.0-jre=compileClasspath,runtimeClasspath\nempty=annotationProcessor\n";
+        assert_eq!(
+            lines("app/gradle.lockfile", old, new),
+            vec!["⬆ com.google.guava:guava 32.1.2-jre → 33.0.0-jre"]
+        );
+    }
+
+    #[test]
+    fn test_sbt_lock() {
+        let old = r#"{"lockVersion": 1, "dependencies": [{"org": "org.typelevel", "name": "cats-core_3", "version": "2.9.0"}]}"#;
+        let new = r#"{"lockVersion": 1, "dependencies": [{"org": "org.typelevel", "name": "cats-core_3", "version": "2.10.0"}]}"#;
+        assert_eq!(
+            lines("build.sbt.lock", old, new),
+            vec!["⬆ org.typelevel:cats-core_3 2.9.0 → 2.10.0"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        assert!(diff("bun.lockb", "", "").is_none());
+        assert!(diff("MODULE.bazel.lock", "{}", "{}").is_none());
+        assert!(is_supported("web/yarn.lock"));
+        assert!(!is_supported("dist/bundle.min.js"));
+    }
+}
@@ -1,20 +1,48 @@
 mod animation;
+mod atomic_file;
+mod bookmarks;
+mod clock;
+mod completion;
 mod config;
+mod encoding;
 mod git;
+mod lockfile;
 mod panes;
+mod playlist;
+mod preview;
+mod record;
+mod state;
+mod storyboard;
 mod syntax;
+#[cfg(test)]
+mod test_support;
 mod theme;
+mod transform;
+mod typing;
 mod ui;
+mod verify;
 mod widgets;
 
-use animation::SpeedRule;
+use animation::{CommitDuration, HunkOrder, SpeedPreset, SpeedRule};
 use anyhow::{Context, Result};
+use bookmarks::Bookmarks;
 use clap::{Parser, Subcommand, ValueEnum};
 use config::Config;
-use git::{DiffMode, GitRepository};
+use git::{
+    DiffAlgorithm, DiffMode, DiffSettings, GitRepository, PlaybackDirection, ResumeOutcome,
+    WhitespaceCommits,
+};
+use playlist::Playlist;
+use preview::ImageProtocol;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use record::RecordFormat;
+use state::StateStore;
 use std::path::{Path, PathBuf};
+use storyboard::Storyboard;
 use theme::Theme;
-use ui::UI;
+use typing::{TypingSettings, TypingStyle};
+use ui::{Source, SourceRotation, UI};
 
 /// Defines the order in which commits are played back during animation.
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -23,6 +51,7 @@ pub enum PlaybackOrder {
     Random,
     Asc,
     Desc,
+    Shuffle,
 }
 
 #[derive(Parser, Debug)]
@@ -37,9 +66,10 @@ pub struct Args {
         short,
         long,
         value_name = "PATH",
-        help = "Path to Git repository (defaults to current directory)"
+        action = clap::ArgAction::Append,
+        help = "Path to Git repository, or a directory of repositories (defaults to current directory, can be specified multiple times)"
     )]
-    pub path: Option<PathBuf>,
+    pub path: Vec<PathBuf>,
 
     #[arg(
         short,
@@ -91,6 +121,26 @@ pub struct Args {
     )]
     pub loop_playback: Option<bool>,
 
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "commit",
+        help = "Play the repositories, commits and ranges listed in a playlist file, in order (or shuffled with --order random/shuffle)"
+    )]
+    pub playlist: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Continue asc/desc playback after the commit where the last session stopped"
+    )]
+    pub resume: bool,
+
+    #[arg(
+        long,
+        help = "Replay commits without the UI and check that each file ends up as committed"
+    )]
+    pub verify: bool,
+
     #[arg(long, help = "Display third-party license information")]
     pub license: bool,
 
@@ -145,6 +195,126 @@ pub struct Args {
     )]
     pub speed_rule: Vec<String>,
 
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ALGORITHM",
+        help = "Diff algorithm used to compute changes (overrides config file)"
+    )]
+    pub diff_algorithm: Option<DiffAlgorithm>,
+
+    #[arg(
+        long,
+        value_name = "LINES",
+        help = "Unchanged lines shown around each change (overrides config file)"
+    )]
+    pub context_lines: Option<u32>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Ignore whitespace changes when diffing (overrides config file)"
+    )]
+    pub ignore_whitespace: Option<bool>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Ignore added or removed blank lines when diffing (overrides config file)"
+    )]
+    pub ignore_blank_lines: Option<bool>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        help = "Show, compact or skip commits that only change whitespace (overrides config file)"
+    )]
+    pub whitespace_commits: Option<WhitespaceCommits>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PROTOCOL",
+        help = "Draw image previews with half blocks, kitty or sixel graphics; auto detects support (overrides config file)"
+    )]
+    pub image_protocol: Option<ImageProtocol>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Replay the new commits of a checked-out submodule when its pointer changes (overrides config file)"
+    )]
+    pub submodule_replay: Option<bool>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYLE",
+        help = "Typing style: natural (typos, rhythm and pauses) or uniform (overrides config file)"
+    )]
+    pub typing: Option<TypingStyle>,
+
+    #[arg(
+        long,
+        value_name = "RATE",
+        value_parser = parse_typo_rate,
+        help = "Chance of mistyping a letter with natural typing, e.g. 0.02 (overrides config file)"
+    )]
+    pub typo_rate: Option<f64>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Complete identifiers already in the file after a few typed characters (overrides config file)"
+    )]
+    pub autocomplete: Option<bool>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ORDER",
+        help = "Order in which hunks are edited: top-down, bottom-up, signature-first, random, or interleave (overrides config file)"
+    )]
+    pub hunk_order: Option<HunkOrder>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PRESET",
+        help = "Playback rate: relaxed (0.5x), normal, demo (2x), or turbo (8x) (overrides config file)"
+    )]
+    pub speed_preset: Option<SpeedPreset>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Fit each commit into a duration (e.g., 45s) or between bounds (e.g., 30s..2m) by scaling typing speed and pauses (overrides config file)"
+    )]
+    pub commit_duration: Option<CommitDuration>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "File that bookmarks are listed from and saved to (defaults to ~/.local/state/gitlogue/bookmarks.toml)"
+    )]
+    pub bookmarks: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SEED",
+        help = "Seed commit selection and typing variation to make playback reproducible (overrides config file)"
+    )]
+    pub seed: Option<u64>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -188,6 +358,53 @@ pub enum Commands {
               help = "Set typing speed for files matching pattern (e.g., '*.java:50')")]
         speed_rule: Vec<String>,
     },
+    /// Record a commit or range to an asciicast, animated SVG or HTML player file
+    Record {
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "File to write the recording to"
+        )]
+        output: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            help = "Recording format (defaults to the file extension, cast otherwise)"
+        )]
+        format: Option<RecordFormat>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "HASH_OR_RANGE",
+            help = "Commit or range to record (defaults to HEAD)"
+        )]
+        commit: Option<String>,
+
+        #[arg(long, value_name = "COLUMNS", default_value_t = 120, value_parser = clap::value_parser!(u16).range(20..),
+              help = "Width of the recorded terminal")]
+        width: u16,
+
+        #[arg(long, value_name = "ROWS", default_value_t = 36, value_parser = clap::value_parser!(u16).range(10..),
+              help = "Height of the recorded terminal")]
+        height: u16,
+
+        #[arg(long, value_name = "FPS", default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=120),
+              help = "Maximum frames per second")]
+        fps: u32,
+    },
+    /// Present the scenes of a storyboard file, e.g. for a talk or walkthrough
+    Present {
+        #[arg(
+            value_name = "FILE",
+            help = "Storyboard listing the commits, captions and pauses of each scene"
+        )]
+        storyboard: PathBuf,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -204,7 +421,7 @@ pub enum ThemeCommands {
 impl Args {
     /// Validates the command-line arguments and returns the Git repository path.
     pub fn validate(&self) -> Result<PathBuf> {
-        let start_path = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let start_path = self.default_path();
 
         if !start_path.exists() {
             anyhow::bail!("Path does not exist: {}", start_path.display());
@@ -224,6 +441,204 @@ impl Args {
         Ok(repo_path)
     }
 
+    /// Resolves every repository to play. Each path is a repository, or
+    /// inside one, or else a directory whose subdirectories are scanned for
+    /// repositories.
+    pub fn repositories(&self) -> Result<Vec<PathBuf>> {
+        if self.path.len() <= 1 {
+            // A single path outside any repository may be a parent directory
+            let found = self
+                .default_path()
+                .canonicalize()
+                .ok()
+                .filter(|path| Self::find_git_root(path).is_none())
+                .map(|path| Self::scan_repositories(&path))
+                .unwrap_or_default();
+            if !found.is_empty() {
+                return Ok(found);
+            }
+            return self.validate().map(|path| vec![path]);
+        }
+
+        let mut repos = Vec::new();
+        for path in &self.path {
+            let canonical = path
+                .canonicalize()
+                .with_context(|| format!("Path does not exist: {}", path.display()))?;
+            let found = match Self::find_git_root(&canonical) {
+                Some(root) => vec![root],
+                None => Self::scan_repositories(&canonical),
+            };
+            if found.is_empty() {
+                anyhow::bail!("No Git repository found in {}", path.display());
+            }
+            for repo in found {
+                if !repos.contains(&repo) {
+                    repos.push(repo);
+                }
+            }
+        }
+        Ok(repos)
+    }
+
+    // First --path, or the current directory
+    fn default_path(&self) -> PathBuf {
+        self.path
+            .first()
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    // Repositories directly inside `dir`, sorted by path
+    fn scan_repositories(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut repos: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir() && path.join(".git").exists())
+            .collect();
+        repos.sort();
+        repos
+    }
+
+    /// Resolves diff settings: CLI arguments > config file > defaults.
+    pub fn diff_settings(&self, config: &Config) -> DiffSettings {
+        DiffSettings {
+            algorithm: self.diff_algorithm.unwrap_or_else(|| {
+                DiffAlgorithm::from_str(&config.diff_algorithm, true).unwrap_or_default()
+            }),
+            context_lines: self.context_lines.unwrap_or(config.context_lines),
+            ignore_whitespace: self.ignore_whitespace.unwrap_or(config.ignore_whitespace),
+            ignore_blank_lines: self.ignore_blank_lines.unwrap_or(config.ignore_blank_lines),
+            whitespace_commits: self.whitespace_commits.unwrap_or_else(|| {
+                WhitespaceCommits::from_str(&config.whitespace_commits, true).unwrap_or_default()
+            }),
+        }
+    }
+
+    /// Resolves the seed (CLI argument > config file) into the generator that
+    /// every random choice is drawn from; unseeded runs use fresh entropy.
+    pub fn rng(&self, config: &Config) -> StdRng {
+        match self.seed.or(config.seed) {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Resolves the hunk order: CLI argument > config file > top-down.
+    pub fn hunk_order(&self, config: &Config) -> HunkOrder {
+        self.hunk_order
+            .unwrap_or_else(|| HunkOrder::from_str(&config.hunk_order, true).unwrap_or_default())
+    }
+
+    /// Resolves the speed preset: CLI argument > config file > normal.
+    pub fn speed_preset(&self, config: &Config) -> SpeedPreset {
+        self.speed_preset.unwrap_or_else(|| {
+            SpeedPreset::from_str(&config.speed_preset, true).unwrap_or_default()
+        })
+    }
+
+    /// Resolves the commit duration: CLI argument > config file > none.
+    pub fn commit_duration(&self, config: &Config) -> Option<CommitDuration> {
+        self.commit_duration.or_else(|| {
+            let value = config.commit_duration.as_deref()?;
+            value
+                .parse()
+                .map_err(|e| {
+                    eprintln!(
+                        "Warning: Invalid commit_duration '{}' ({}), ignoring",
+                        value, e
+                    )
+                })
+                .ok()
+        })
+    }
+
+    /// Opens the bookmarks file given on the command line, or the default
+    /// one if it can be created.
+    pub fn bookmarks(&self) -> Result<Option<Bookmarks>> {
+        match &self.bookmarks {
+            Some(path) => Bookmarks::open(path).map(Some),
+            None => Ok(Bookmarks::open_default().ok()),
+        }
+    }
+
+    /// Applies the playback options shared by every way of playing commits,
+    /// with `rng` as the source of random choices.
+    pub fn configure_ui(&self, ui: &mut UI, config: &Config, rng: StdRng) -> Result<()> {
+        ui.set_image_protocol(self.image_protocol(config));
+        ui.set_rng(rng);
+        ui.set_typing_settings(&self.typing_settings(config));
+        ui.set_hunk_order(self.hunk_order(config));
+        ui.set_playback_rate(self.speed_preset(config).rate());
+        ui.set_commit_duration(self.commit_duration(config));
+        if let Some(bookmarks) = self.bookmarks()? {
+            ui.set_bookmarks(bookmarks);
+        }
+        Ok(())
+    }
+
+    /// Parses speed rules: CLI arguments take priority, then the config file.
+    pub fn speed_rules(&self, config: &Config) -> Vec<SpeedRule> {
+        self.speed_rule
+            .iter()
+            .chain(config.speed_rules.iter())
+            .filter_map(|s| {
+                SpeedRule::parse(s).or_else(|| {
+                    eprintln!("Warning: Invalid speed rule '{}', skipping", s);
+                    None
+                })
+            })
+            .collect()
+    }
+
+    /// Collects ignore patterns: config file, then the ignore file, then CLI flags.
+    pub fn ignore_patterns(&self, config: &Config) -> Vec<String> {
+        let mut patterns = config.ignore_patterns.clone();
+        if let Some(path) = &self.ignore_file {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                patterns.extend(
+                    content
+                        .lines()
+                        .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+                        .map(String::from),
+                );
+            }
+        }
+        patterns.extend(self.ignore.clone());
+        patterns
+    }
+
+    /// Resolves typing settings: CLI arguments > config file > defaults.
+    pub fn typing_settings(&self, config: &Config) -> TypingSettings {
+        let defaults = TypingSettings::default();
+        let typo_rate = match self.typo_rate {
+            Some(rate) => rate,
+            None => config_factor("typo_rate", config.typo_rate, defaults.typo_rate),
+        };
+        TypingSettings {
+            style: self
+                .typing
+                .unwrap_or_else(|| TypingStyle::from_str(&config.typing, true).unwrap_or_default()),
+            typo_rate: typo_rate.clamp(0.0, 1.0),
+            punctuation_pause: config_factor(
+                "punctuation_pause",
+                config.punctuation_pause,
+                defaults.punctuation_pause,
+            ),
+            brace_pause: config_factor("brace_pause", config.brace_pause, defaults.brace_pause),
+            autocomplete: self.autocomplete.unwrap_or(config.autocomplete),
+        }
+    }
+
+    /// Resolves the image preview protocol: CLI argument > config file > auto.
+    pub fn image_protocol(&self, config: &Config) -> ImageProtocol {
+        self.image_protocol.unwrap_or_else(|| {
+            ImageProtocol::from_str(&config.image_protocol, true).unwrap_or_default()
+        })
+    }
+
     fn find_git_root(start_path: &Path) -> Option<PathBuf> {
         let mut current = if start_path.is_file() {
             start_path.parent()?.to_path_buf()
@@ -242,8 +657,40 @@ impl Args {
     }
 }
 
+/// Loads the theme `name`, making its background transparent unless
+/// `background` is set.
+fn load_theme(name: &str, background: bool) -> Result<Theme> {
+    let theme = Theme::load(name)?;
+    Ok(if background {
+        theme
+    } else {
+        theme.with_transparent_background()
+    })
+}
+
+fn parse_typo_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        _ => Err("Typo rate must be a number from 0 to 1".to_string()),
+    }
+}
+
+// A finite, non-negative number from the config file, or `default` with a
+// warning
+fn config_factor(name: &str, value: f64, default: f64) -> f64 {
+    if value.is_finite() && value >= 0.0 {
+        value
+    } else {
+        eprintln!(
+            "Warning: Invalid {} '{}' in config file, using {}",
+            name, value, default
+        );
+        default
+    }
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Handle --license flag
     if args.license {
@@ -286,7 +733,10 @@ fn main() -> Result<()> {
                 speed_rule,
             } => {
                 let repo_path = args.validate()?;
-                let repo = GitRepository::open(&repo_path)?;
+                let mut repo = GitRepository::open(&repo_path)?;
+                let config = Config::load()?;
+                repo.set_diff_settings(args.diff_settings(&config));
+                repo.set_submodule_replay(args.submodule_replay.unwrap_or(config.submodule_replay));
 
                 let mode = if *unstaged {
                     DiffMode::Unstaged
@@ -301,8 +751,6 @@ fn main() -> Result<()> {
                     return Ok(());
                 }
 
-                let config = Config::load()?;
-
                 let mut patterns = config.ignore_patterns.clone();
                 patterns.extend(ignore.clone());
                 git::init_ignore_patterns(&patterns).ok();
@@ -312,10 +760,7 @@ fn main() -> Result<()> {
                 let background = background.unwrap_or(config.background);
                 let loop_playback = loop_playback.unwrap_or(false);
 
-                let mut theme = Theme::load(theme_name)?;
-                if !background {
-                    theme = theme.with_transparent_background();
-                }
+                let theme = load_theme(theme_name, background)?;
 
                 let speed_rules: Vec<SpeedRule> = speed_rule
                     .iter()
@@ -328,44 +773,44 @@ fn main() -> Result<()> {
                     })
                     .collect();
 
-                // Create UI - pass repo ref only if looping (to refresh diff)
-                let repo_ref = if loop_playback { Some(&repo) } else { None };
+                // Create UI - pass the repo only if looping (to refresh diff)
+                let sources = if loop_playback {
+                    vec![Source::new(repo, None)]
+                } else {
+                    Vec::new()
+                };
                 let mut ui = UI::new(
                     speed,
-                    repo_ref,
+                    sources,
                     theme,
                     PlaybackOrder::Asc,
                     loop_playback,
-                    None,
-                    false,
                     speed_rules,
                 );
                 ui.set_diff_mode(Some(mode));
+                args.configure_ui(&mut ui, &config, args.rng(&config))?;
                 ui.load_commit(metadata);
                 ui.run()?;
 
                 return Ok(());
             }
+            Commands::Present { storyboard } => return present(&args, storyboard),
+            Commands::Record { commit, .. } => {
+                // Recordings cover one commit or range and must end
+                args.commit = commit
+                    .clone()
+                    .or(args.commit.take())
+                    .or(Some("HEAD".to_string()));
+                args.loop_playback = Some(false);
+            }
         }
     }
 
-    let repo_path = args.validate()?;
-    let mut repo = GitRepository::open(&repo_path)?;
-
-    // Set author filter if specified
-    if args.author.is_some() {
-        repo.set_author_filter(args.author.clone());
+    if let Some(path) = &args.playlist {
+        return play_playlist(&args, path);
     }
 
-    // Set date filters if specified
-    if let Some(ref before_str) = args.before {
-        let before_date = git::parse_date(before_str)?;
-        repo.set_before_filter(Some(before_date));
-    }
-    if let Some(ref after_str) = args.after {
-        let after_date = git::parse_date(after_str)?;
-        repo.set_after_filter(Some(after_date));
-    }
+    let repo_paths = args.repositories()?;
 
     let is_commit_specified = args.commit.is_some();
     let is_range_mode = args
@@ -374,30 +819,25 @@ fn main() -> Result<()> {
         .map(|c| c.contains(".."))
         .unwrap_or(false);
     let is_filtered = args.author.is_some() || args.before.is_some() || args.after.is_some();
+    if repo_paths.len() > 1 && is_commit_specified && !is_range_mode {
+        anyhow::bail!("--commit must be a range when playing several repositories");
+    }
 
     // Load config: CLI arguments > config file > defaults
     let config = Config::load()?;
 
+    // One seeded generator feeds commit selection and playback timing
+    let mut rng = args.rng(&config);
+
     // Initialize ignore patterns: CLI flags > ignore-file > config
-    let mut patterns = config.ignore_patterns.clone();
-    if let Some(path) = &args.ignore_file {
-        if let Ok(content) = std::fs::read_to_string(path) {
-            patterns.extend(
-                content
-                    .lines()
-                    .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
-                    .map(String::from),
-            );
-        }
-    }
-    patterns.extend(args.ignore.clone());
-    git::init_ignore_patterns(&patterns).ok();
+    git::init_ignore_patterns(&args.ignore_patterns(&config)).ok();
     let theme_name = args.theme.as_deref().unwrap_or(&config.theme);
     let speed = args.speed.unwrap_or(config.speed);
     let background = args.background.unwrap_or(config.background);
     let mut order = args.order.unwrap_or(match config.order.as_str() {
         "asc" => PlaybackOrder::Asc,
         "desc" => PlaybackOrder::Desc,
+        "shuffle" => PlaybackOrder::Shuffle,
         _ => PlaybackOrder::Random,
     });
 
@@ -407,69 +847,333 @@ fn main() -> Result<()> {
     }
 
     let loop_playback = args.loop_playback.unwrap_or(config.loop_playback);
-    let mut theme = Theme::load(theme_name)?;
+    let theme = load_theme(theme_name, background)?;
+
+    // Remember shown commits across sessions (best-effort). Seeded random
+    // playback ignores earlier sessions so that it can be reproduced.
+    let is_seeded_random = args.seed.or(config.seed).is_some()
+        && matches!(order, PlaybackOrder::Random | PlaybackOrder::Shuffle);
+
+    let mut repos = Vec::with_capacity(repo_paths.len());
+    for repo_path in &repo_paths {
+        let mut repo = GitRepository::open(repo_path)?;
+        repo.set_author_filter(args.author.clone());
+        repo.set_before_filter(args.before.as_deref())?;
+        repo.set_after_filter(args.after.as_deref())?;
+        repo.set_diff_settings(args.diff_settings(&config));
+        repo.set_submodule_replay(args.submodule_replay.unwrap_or(config.submodule_replay));
+        repo.set_rng(rng.fork());
 
-    // Apply transparent background if requested
-    if !background {
-        theme = theme.with_transparent_background();
+        // Setup commit range if specified
+        if is_range_mode {
+            repo.set_commit_range(args.commit.as_ref().unwrap())?;
+        }
+
+        if !is_seeded_random {
+            if let Ok(store) = StateStore::open_default() {
+                repo.set_state_store(store);
+            }
+        }
+        repos.push(repo);
     }
 
-    // Setup commit range if specified
-    if is_range_mode {
-        repo.set_commit_range(args.commit.as_ref().unwrap())?;
+    if args.verify {
+        for repo in &repos {
+            verify(
+                repo,
+                args.commit.as_deref(),
+                is_range_mode,
+                args.hunk_order(&config),
+            )?;
+        }
+        return Ok(());
     }
 
-    // Load initial commit
-    let metadata = if is_range_mode {
-        match order {
-            PlaybackOrder::Random => repo.random_range_commit()?,
-            PlaybackOrder::Asc => repo.next_range_commit_asc()?,
-            PlaybackOrder::Desc => repo.next_range_commit_desc()?,
+    // Continue from the last session's position (asc/desc only)
+    if args.resume {
+        let direction = match order {
+            PlaybackOrder::Asc => Some(PlaybackDirection::Asc),
+            PlaybackOrder::Desc => Some(PlaybackDirection::Desc),
+            PlaybackOrder::Random | PlaybackOrder::Shuffle => None,
+        };
+        match direction {
+            Some(_) if is_commit_specified && !is_range_mode => {
+                eprintln!("Warning: --resume has no effect with a single --commit");
+            }
+            Some(direction) => {
+                for (repo, path) in repos.iter().zip(&repo_paths) {
+                    let name = repository_name(path).unwrap_or_else(|| path.display().to_string());
+                    match repo.resume(direction)? {
+                        ResumeOutcome::NoCursor => {
+                            eprintln!("{}: No saved position for this order and filters, starting from the beginning", name);
+                        }
+                        ResumeOutcome::Rewritten => {
+                            eprintln!(
+                                "{}: Last played commit no longer exists, resuming from its date",
+                                name
+                            );
+                        }
+                        ResumeOutcome::Restarted => {
+                            eprintln!(
+                                "{}: All commits were played last time, starting from the beginning",
+                                name
+                            );
+                        }
+                        ResumeOutcome::Resumed => {}
+                    }
+                }
+            }
+            None => {
+                eprintln!("Warning: --resume only applies to --order asc or desc");
+            }
         }
-    } else if let Some(commit_hash) = &args.commit {
-        repo.get_commit(commit_hash)?
+    }
+
+    let (sources, metadata, rotation) = if repos.len() == 1 {
+        // Load initial commit
+        let mut source = Source::new(repos.remove(0), args.commit.clone());
+        let metadata = source.next_commit(order)?;
+
+        // A single commit without --loop is played once; other modes, including
+        // filtered ones (range/author/date), keep the repository for iteration
+        let is_single_commit = is_commit_specified && !is_range_mode && !is_filtered;
+        let sources = if is_single_commit && !loop_playback {
+            Vec::new()
+        } else {
+            vec![source]
+        };
+        (sources, Some(metadata), SourceRotation::InTurn)
     } else {
-        match order {
-            PlaybackOrder::Random => repo.random_commit()?,
-            PlaybackOrder::Asc => repo.next_asc_commit()?,
-            PlaybackOrder::Desc => repo.next_desc_commit()?,
+        // Random orders pick each commit's repository in proportion to its
+        // number of commits, so that busier repositories come up more often;
+        // asc and desc play the repositories in turn
+        let mut sources = Vec::with_capacity(repos.len());
+        for (repo, path) in repos.into_iter().zip(&repo_paths) {
+            let weight = match repo.commit_count() {
+                Ok(count) if count > 0 => count as u64,
+                _ => {
+                    eprintln!(
+                        "Warning: No commits to play in {}, skipping",
+                        path.display()
+                    );
+                    continue;
+                }
+            };
+            let mut source = Source::new(repo, args.commit.clone());
+            source.name = repository_name(path);
+            source.weight = weight;
+            sources.push(source);
         }
+        let rotation = match order {
+            PlaybackOrder::Random | PlaybackOrder::Shuffle => SourceRotation::Weighted,
+            PlaybackOrder::Asc | PlaybackOrder::Desc => SourceRotation::InTurn,
+        };
+        (sources, None, rotation)
     };
 
-    // Parse speed rules: CLI args take priority, then config file
-    let speed_rules: Vec<SpeedRule> = args
-        .speed_rule
-        .iter()
-        .chain(config.speed_rules.iter())
-        .filter_map(|s| {
-            SpeedRule::parse(s).or_else(|| {
-                eprintln!("Warning: Invalid speed rule '{}', skipping", s);
-                None
-            })
-        })
-        .collect();
-
-    // Create UI with repository reference
-    // Filtered modes (range/author/date) always need repo ref for iteration
-    let repo_ref = if is_range_mode || is_filtered {
-        Some(&repo)
-    } else if is_commit_specified && !loop_playback {
-        None
-    } else {
-        Some(&repo)
-    };
     let mut ui = UI::new(
         speed,
-        repo_ref,
+        sources,
         theme,
         order,
         loop_playback,
-        args.commit.clone(),
-        is_range_mode,
-        speed_rules,
+        args.speed_rules(&config),
     );
-    ui.load_commit(metadata);
-    ui.run()?;
+    args.configure_ui(&mut ui, &config, rng.fork())?;
+    ui.set_source_rotation(rotation);
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => ui
+            .next_commit()
+            .context("No commits to play in any repository")?,
+    };
+    play(&args, ui, metadata)
+}
 
+/// Name a repository is shown by: its directory name.
+fn repository_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Records `metadata` and the commits after it for the `record` subcommand,
+/// or plays them.
+fn play(args: &Args, mut ui: UI, metadata: git::CommitMetadata) -> Result<()> {
+    if let Some(Commands::Record {
+        output,
+        format,
+        width,
+        height,
+        fps,
+        ..
+    }) = &args.command
+    {
+        let format = format.unwrap_or_else(|| RecordFormat::from_path(output));
+        ui.record(metadata, output, format, *width, *height, *fps)?;
+        println!("Recording saved to {}", output.display());
+    } else {
+        ui.load_commit(metadata);
+        ui.run()?;
+    }
+
+    Ok(())
+}
+
+/// Plays the entries of the playlist at `path` in turn, each from its own
+/// repository handle.
+fn play_playlist(args: &Args, path: &Path) -> Result<()> {
+    let playlist = Playlist::load(path)?;
+    let config = Config::load()?;
+    git::init_ignore_patterns(&args.ignore_patterns(&config)).ok();
+
+    let background = args.background.unwrap_or(config.background);
+    let theme = load_theme(args.theme.as_deref().unwrap_or(&config.theme), background)?;
+
+    // Entries play in order and their commits oldest first, unless asked
+    // otherwise on the command line
+    let order = match args.order {
+        Some(PlaybackOrder::Desc) => PlaybackOrder::Desc,
+        _ => PlaybackOrder::Asc,
+    };
+    let rotation = match args.order {
+        Some(PlaybackOrder::Random | PlaybackOrder::Shuffle) => SourceRotation::Shuffled,
+        _ => SourceRotation::InTurn,
+    };
+    let mut rng = args.rng(&config);
+
+    let default_repo = args.default_path();
+    let mut sources = Vec::with_capacity(playlist.entries.len());
+    for (index, entry) in playlist.entries.iter().enumerate() {
+        let repo_path = playlist.repo_path(entry, &default_repo);
+        let source = (|| {
+            let canonical = repo_path
+                .canonicalize()
+                .with_context(|| format!("Path does not exist: {}", repo_path.display()))?;
+            let root = Args::find_git_root(&canonical)
+                .with_context(|| format!("Not a Git repository: {}", repo_path.display()))?;
+            let mut repo = GitRepository::open(&root)?;
+            repo.set_diff_settings(args.diff_settings(&config));
+            repo.set_submodule_replay(args.submodule_replay.unwrap_or(config.submodule_replay));
+            repo.set_rng(rng.fork());
+            repo.set_author_filter(entry.author.clone().or(args.author.clone()));
+            repo.set_before_filter(entry.before.as_deref().or(args.before.as_deref()))?;
+            repo.set_after_filter(entry.after.as_deref().or(args.after.as_deref()))?;
+            if let Some(range) = entry.commit.as_ref().filter(|c| c.contains("..")) {
+                repo.set_commit_range(range)?;
+            }
+
+            let mut source = Source::new(repo, entry.commit.clone());
+            source.name = repository_name(&root);
+            source.theme = entry
+                .theme
+                .as_deref()
+                .map(|name| load_theme(name, background))
+                .transpose()?;
+            source.speed = entry.speed;
+            Ok::<_, anyhow::Error>(source)
+        })()
+        .with_context(|| format!("Playlist entry {}", index + 1))?;
+        sources.push(source);
+    }
+    // Repository names are only shown when the playlist spans several
+    if sources.iter().all(|source| source.name == sources[0].name) {
+        sources.iter_mut().for_each(|source| source.name = None);
+    }
+
+    let mut ui = UI::new(
+        args.speed.unwrap_or(config.speed),
+        sources,
+        theme,
+        order,
+        args.loop_playback.unwrap_or(config.loop_playback),
+        args.speed_rules(&config),
+    );
+    args.configure_ui(&mut ui, &config, rng.fork())?;
+    ui.set_source_rotation(rotation);
+
+    let metadata = ui
+        .next_commit()
+        .context("Playlist has no commits to play")?;
+    play(args, ui, metadata)
+}
+
+/// Plays the scenes of the storyboard at `path`.
+fn present(args: &Args, path: &Path) -> Result<()> {
+    let storyboard = Storyboard::load(path)?;
+    let config = Config::load()?;
+    let diff_settings = args.diff_settings(&config);
+    let submodule_replay = args.submodule_replay.unwrap_or(config.submodule_replay);
+
+    git::init_ignore_patterns(&args.ignore_patterns(&config)).ok();
+
+    // Scenes without a repository use --path or the current directory
+    let default_repo = args.default_path();
+    let scenes = storyboard.resolve(&default_repo, |path| {
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Path does not exist: {}", path.display()))?;
+        let root = Args::find_git_root(&canonical)
+            .with_context(|| format!("Not a Git repository: {}", path.display()))?;
+        let mut repo = GitRepository::open(root)?;
+        repo.set_diff_settings(diff_settings.clone());
+        repo.set_submodule_replay(submodule_replay);
+        Ok(repo)
+    })?;
+
+    // Theme: CLI argument > storyboard > config file
+    let theme_name = args
+        .theme
+        .as_deref()
+        .or(storyboard.theme.as_deref())
+        .unwrap_or(&config.theme);
+    let theme = load_theme(theme_name, args.background.unwrap_or(config.background))?;
+
+    // Scene speeds fall back to the storyboard's, then to --speed and config
+    let speed = args.speed.unwrap_or(config.speed);
+    let mut ui = UI::new(
+        speed,
+        Vec::new(),
+        theme,
+        PlaybackOrder::Asc,
+        false,
+        args.speed_rules(&config),
+    );
+    args.configure_ui(&mut ui, &config, args.rng(&config))?;
+    ui.present(scenes)
+}
+
+/// Replays the selected commits headlessly and reports files that do not
+/// reproduce their committed content.
+fn verify(
+    repo: &GitRepository,
+    commit: Option<&str>,
+    is_range_mode: bool,
+    order: HunkOrder,
+) -> Result<()> {
+    let hashes = match commit {
+        Some(hash) if !is_range_mode => vec![hash.to_string()],
+        _ => repo.commit_hashes()?,
+    };
+
+    let mut mismatches = 0;
+    for hash in &hashes {
+        let metadata = repo.get_commit(hash)?;
+        for mismatch in verify::verify_commit(&metadata, order) {
+            println!("{}", mismatch);
+            mismatches += 1;
+        }
+    }
+
+    println!(
+        "Verified {} commit{}: {} mismatch{}",
+        hashes.len(),
+        if hashes.len() == 1 { "" } else { "s" },
+        mismatches,
+        if mismatches == 1 { "" } else { "es" }
+    );
+
+    if mismatches > 0 {
+        anyhow::bail!("Playback did not reproduce {} file(s)", mismatches);
+    }
     Ok(())
 }
@@ -1,16 +1,26 @@
+use std::sync::Arc;
+
+use image::{DynamicImage, RgbaImage};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Padding},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
     Frame,
 };
+use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
+use unicode_width::UnicodeWidthStr;
 
-use crate::animation::{ActivePane, AnimationEngine};
+use crate::animation::{ActivePane, AnimationEngine, CompletionPopup};
+use crate::preview::{format_size, BinaryChange, BinarySide};
 use crate::theme::Theme;
 use crate::widgets::SelectableParagraph;
 
-pub struct EditorPane;
+pub struct EditorPane {
+    picker: Picker,
+    // Encoded images of the current preview, keyed by the image they show
+    images: Vec<(Arc<RgbaImage>, StatefulProtocol)>,
+}
 
 struct HighlightContext<'a> {
     line_content: &'a str,
@@ -27,11 +37,38 @@ struct HighlightContext<'a> {
 }
 
 impl EditorPane {
-    pub fn render(&self, f: &mut Frame, area: Rect, engine: &AnimationEngine, theme: &Theme) {
+    pub fn new() -> Self {
+        Self {
+            picker: Picker::halfblocks(),
+            images: Vec::new(),
+        }
+    }
+
+    /// Sets how image previews are drawn (half blocks, kitty or sixel graphics).
+    pub fn set_picker(&mut self, picker: Picker) {
+        self.picker = picker;
+        self.images.clear();
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, engine: &AnimationEngine, theme: &Theme) {
         let block = Block::default()
             .style(Style::default().bg(theme.background_right))
             .padding(Padding::vertical(1));
 
+        if let Some(binary) = engine.current_binary() {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            let inner = inner.inner(ratatui::layout::Margin::new(2, 0));
+            if binary.is_image() {
+                self.render_images(f, inner, binary, theme);
+            } else {
+                self.images.clear();
+                render_binary_summary(f, inner, binary, theme);
+            }
+            return;
+        }
+        self.images.clear();
+
         let content_height = area.height.saturating_sub(2) as usize; // Subtract top and bottom padding
         let scroll_offset = engine.buffer.scroll_offset;
         let buffer_lines = &engine.buffer.lines;
@@ -68,6 +105,70 @@ impl EditorPane {
             .padding(Padding::horizontal(2))
             .dim(20, 0.6);
         f.render_widget(content, area);
+
+        if let Some(popup) = &engine.completion {
+            render_completion(f, area, popup, line_num_width, engine, theme);
+        }
+    }
+
+    // Before and after images side by side, each under a caption
+    fn render_images(&mut self, f: &mut Frame, area: Rect, binary: &BinaryChange, theme: &Theme) {
+        let shown: Vec<&Arc<RgbaImage>> = [&binary.old, &binary.new]
+            .into_iter()
+            .flatten()
+            .filter_map(|side| side.image.as_ref())
+            .collect();
+        self.images
+            .retain(|(image, _)| shown.iter().any(|shown| Arc::ptr_eq(image, shown)));
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .spacing(2)
+            .split(area);
+
+        for (column, (label, side)) in columns
+            .iter()
+            .zip([("Before", &binary.old), ("After", &binary.new)])
+        {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)])
+                .split(*column);
+
+            let caption = Line::from(vec![
+                Span::styled(
+                    label,
+                    Style::default()
+                        .fg(theme.syntax_keyword)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {}", side_caption(side)),
+                    Style::default().fg(theme.syntax_comment),
+                ),
+            ]);
+            f.render_widget(Paragraph::new(caption), rows[0]);
+
+            let Some(image) = side.as_ref().and_then(|side| side.image.as_ref()) else {
+                continue;
+            };
+            let index = match self
+                .images
+                .iter()
+                .position(|(cached, _)| Arc::ptr_eq(cached, image))
+            {
+                Some(index) => index,
+                None => {
+                    let protocol = self
+                        .picker
+                        .new_resize_protocol(DynamicImage::ImageRgba8((**image).clone()));
+                    self.images.push((image.clone(), protocol));
+                    self.images.len() - 1
+                }
+            };
+            f.render_stateful_widget(StatefulImage::default(), rows[1], &mut self.images[index].1);
+        }
     }
 
     fn build_line(
@@ -270,3 +371,142 @@ impl EditorPane {
             .unwrap_or(theme.syntax_variable) // Use theme color instead of Color::White
     }
 }
+
+// Completion candidates below the identifier being typed, or above it if
+// there is no room below
+fn render_completion(
+    f: &mut Frame,
+    area: Rect,
+    popup: &CompletionPopup,
+    line_num_width: usize,
+    engine: &AnimationEngine,
+    theme: &Theme,
+) {
+    let buffer = &engine.buffer;
+    if popup.line < buffer.scroll_offset || popup.line >= buffer.lines.len() {
+        return;
+    }
+
+    // Same layout as the lines: top padding, left padding, line number and separator
+    let text_x = 2 + line_num_width + 1 + 2;
+    let text_width = (area.width as usize).saturating_sub(text_x + 2).max(1);
+    let before: String = buffer.lines[popup.line].chars().take(popup.col).collect();
+    let rows_above: usize = buffer.lines[buffer.scroll_offset..popup.line]
+        .iter()
+        .map(|line| engine.calculate_line_display_height(line))
+        .sum();
+    let row = 1 + rows_above + before.width() / text_width;
+    let col = text_x + before.width() % text_width;
+
+    let width = popup
+        .candidates
+        .iter()
+        .map(|c| c.width())
+        .max()
+        .unwrap_or(0)
+        + 4;
+    let height = popup.candidates.len() + 2;
+    let y = if row + 1 + height <= area.height as usize {
+        row + 1
+    } else if row >= height {
+        row - height
+    } else {
+        return;
+    };
+    let x = col
+        .saturating_sub(2)
+        .min((area.width as usize).saturating_sub(width));
+    let popup_area = Rect {
+        x: area.x + x as u16,
+        y: area.y + y as u16,
+        width: (width as u16).min(area.width),
+        height: height as u16,
+    };
+
+    let lines: Vec<Line> = popup
+        .candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| {
+            let style = if i == popup.selected {
+                Style::default()
+                    .fg(theme.file_tree_current_file_fg)
+                    .bg(theme.file_tree_current_file_bg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.syntax_variable)
+            };
+            Line::from(Span::styled(
+                format!("{:<width$}", candidate, width = width - 4),
+                style,
+            ))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(
+            Style::default()
+                .fg(theme.editor_separator)
+                .bg(theme.editor_cursor_line_bg),
+        );
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+// Size and dimensions of one side of a preview, or why there is nothing to show
+fn side_caption(side: &Option<BinarySide>) -> String {
+    match side {
+        None => "(none)".to_string(),
+        Some(side) => match side.dimensions {
+            Some((width, height)) => {
+                format!("{}×{} · {}", width, height, format_size(side.size))
+            }
+            None => format!("{} · not an image", format_size(side.size)),
+        },
+    }
+}
+
+// Size change, hex dump and printable strings of a non-image binary file
+fn render_binary_summary(f: &mut Frame, area: Rect, binary: &BinaryChange, theme: &Theme) {
+    let heading = |text: &'static str| {
+        Line::from(Span::styled(
+            text,
+            Style::default()
+                .fg(theme.syntax_keyword)
+                .add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let mut lines = vec![
+        heading("Binary file"),
+        Line::from(Span::styled(
+            binary.size_change(),
+            Style::default().fg(theme.syntax_variable),
+        )),
+        Line::default(),
+    ];
+
+    if !binary.hex_dump.is_empty() {
+        lines.extend(binary.hex_dump.iter().map(|row| {
+            Line::from(Span::styled(
+                row.clone(),
+                Style::default().fg(theme.syntax_number),
+            ))
+        }));
+        lines.push(Line::default());
+    }
+
+    if !binary.strings.is_empty() {
+        lines.push(heading("Strings"));
+        lines.extend(binary.strings.iter().map(|string| {
+            Line::from(Span::styled(
+                format!("{:?}", string),
+                Style::default().fg(theme.syntax_string),
+            ))
+        }));
+    }
+
+    f.render_widget(Paragraph::new(lines), area);
+}
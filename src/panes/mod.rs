@@ -5,5 +5,5 @@ mod terminal;
 
 pub use editor::EditorPane;
 pub use file_tree::FileTreePane;
-pub use status_bar::StatusBarPane;
+pub use status_bar::{Progress, StatusBarPane};
 pub use terminal::TerminalPane;
@@ -6,10 +6,22 @@ use ratatui::{
     Frame,
 };
 
+use std::ops::Range;
+
 use crate::git::CommitMetadata;
 use crate::theme::Theme;
 use crate::widgets::SelectableParagraph;
 
+/// Playback position shown above the commit info
+pub struct Progress {
+    /// Steps played and steps of the commit
+    pub step: usize,
+    pub total: usize,
+    pub rate: f64,
+    /// Steps being repeated by an A-B loop
+    pub loop_range: Option<Range<usize>>,
+}
+
 pub struct StatusBarPane;
 
 impl StatusBarPane {
@@ -18,6 +30,7 @@ impl StatusBarPane {
         f: &mut Frame,
         area: Rect,
         metadata: Option<&CommitMetadata>,
+        progress: &Progress,
         theme: &Theme,
     ) {
         let block = Block::default()
@@ -32,16 +45,25 @@ impl StatusBarPane {
                 &meta.hash[..7.min(meta.hash.len())]
             };
 
-            let mut lines = vec![
-                Line::from(vec![
-                    Span::raw("hash: "),
-                    Span::styled(hash_display, Style::default().fg(theme.status_hash)),
-                ]),
-                Line::from(vec![
-                    Span::raw("author: "),
-                    Span::styled(&meta.author, Style::default().fg(theme.status_author)),
-                ]),
-            ];
+            let mut lines = vec![Self::progress_line(
+                progress,
+                area.width.saturating_sub(4),
+                theme,
+            )];
+            if let Some(repository) = &meta.repository {
+                lines.push(Line::from(vec![
+                    Span::raw("repo: "),
+                    Span::styled(repository, Style::default().fg(theme.status_hash)),
+                ]));
+            }
+            lines.push(Line::from(vec![
+                Span::raw("hash: "),
+                Span::styled(hash_display, Style::default().fg(theme.status_hash)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("author: "),
+                Span::styled(&meta.author, Style::default().fg(theme.status_author)),
+            ]));
 
             // Only show date for actual commits (not working tree)
             if !is_working_tree {
@@ -77,4 +99,51 @@ impl StatusBarPane {
 
         f.render_widget(content, area);
     }
+
+    // Bar of `width` cells filled in proportion to the steps played so far,
+    // with any loop highlighted, followed by the percentage and the rate
+    fn progress_line(progress: &Progress, width: u16, theme: &Theme) -> Line<'static> {
+        let ratio = if progress.total == 0 {
+            1.0
+        } else {
+            progress.step.min(progress.total) as f64 / progress.total as f64
+        };
+        let label = format!(
+            " {:>3}% {:>5}",
+            (ratio * 100.0) as u32,
+            format!("{}x", progress.rate)
+        );
+        let bar_width = (width as usize).saturating_sub(label.len());
+        let filled = (bar_width as f64 * ratio).round() as usize;
+
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        let mut run = String::new();
+        let mut run_color = None;
+        for cell in 0..bar_width {
+            let step = cell * progress.total / bar_width.max(1);
+            let looped = progress
+                .loop_range
+                .as_ref()
+                .is_some_and(|range| range.contains(&step));
+            let color = match (looped, cell < filled) {
+                (true, _) => theme.status_author,
+                (false, true) => theme.status_hash,
+                (false, false) => theme.separator,
+            };
+            if let Some(previous) = run_color.filter(|&c| c != color) {
+                spans.push(Span::styled(
+                    std::mem::take(&mut run),
+                    Style::default().fg(previous),
+                ));
+            }
+            run_color = Some(color);
+            run.push(if cell < filled { '━' } else { '─' });
+        }
+        if let Some(color) = run_color {
+            spans.push(Span::styled(run, Style::default().fg(color)));
+        }
+        spans.push(Span::styled(label, Style::default().fg(theme.status_date)));
+
+        Line::from(spans)
+    }
 }
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entries played in turn by `--playlist`, read from a TOML file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Playlist {
+    #[serde(default, rename = "entry")]
+    pub entries: Vec<PlaylistEntry>,
+    /// Directory that repository paths are relative to
+    #[serde(skip)]
+    base: PathBuf,
+}
+
+/// One `[[entry]]` of a playlist
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlaylistEntry {
+    /// Repository, relative to the playlist file
+    pub repo: Option<PathBuf>,
+    /// Commit or `A..B` range; the whole history if unset
+    pub commit: Option<String>,
+    /// Author name or email filter (partial match, case-insensitive)
+    pub author: Option<String>,
+    /// Only commits before or after these dates
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub theme: Option<String>,
+    /// Typing speed in milliseconds per character
+    pub speed: Option<u64>,
+}
+
+impl Playlist {
+    /// Reads the playlist at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read playlist: {}", path.display()))?;
+        let mut playlist: Self = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse playlist: {}", path.display()))?;
+        playlist.base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(playlist)
+    }
+
+    /// Path of the repository of `entry`, or `default_repo` if it names none.
+    pub fn repo_path(&self, entry: &PlaylistEntry, default_repo: &Path) -> PathBuf {
+        entry
+            .repo
+            .as_ref()
+            .map(|repo| self.base.join(repo))
+            .unwrap_or_else(|| default_repo.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_playlist() {
+        let dir = std::env::temp_dir().join(format!("gitlogue-playlist-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("playlist.toml");
+        fs::write(
+            &path,
+            r#"
+            [[entry]]
+            repo = "../api"
+            commit = "v1.0..v1.1"
+            theme = "nord"
+
+            [[entry]]
+            author = "alice"
+            after = "2024-01-01"
+            speed = 15
+            "#,
+        )
+        .unwrap();
+
+        let playlist = Playlist::load(&path).unwrap();
+        assert_eq!(playlist.entries.len(), 2);
+        assert_eq!(playlist.entries[0].commit.as_deref(), Some("v1.0..v1.1"));
+        assert_eq!(playlist.entries[1].speed, Some(15));
+        assert_eq!(
+            playlist.repo_path(&playlist.entries[0], Path::new(".")),
+            dir.join("../api")
+        );
+        assert_eq!(
+            playlist.repo_path(&playlist.entries[1], Path::new("here")),
+            PathBuf::from("here")
+        );
+
+        fs::write(&path, "[[entry]]\nrevision = \"HEAD\"\n").unwrap();
+        assert!(Playlist::load(&path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
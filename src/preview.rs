@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use image::{imageops::FilterType, RgbaImage};
+use resvg::{tiny_skia, usvg};
+
+/// Largest edge of a decoded image kept for previews, in pixels
+const MAX_THUMBNAIL_SIZE: u32 = 256;
+/// Bytes shown in the hex dump of a binary file
+const HEX_DUMP_BYTES: usize = 48;
+const HEX_DUMP_WIDTH: usize = 16;
+/// Shortest run of printable characters listed as a string
+const MIN_STRING_LEN: usize = 5;
+const MAX_STRINGS: usize = 6;
+const MAX_STRING_WIDTH: usize = 48;
+
+/// How image previews are drawn in the editor pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImageProtocol {
+    /// Kitty or sixel graphics if the terminal answers for them, half blocks otherwise
+    #[default]
+    Auto,
+    /// Half-block characters, which work in every terminal
+    Halfblocks,
+    Kitty,
+    Sixel,
+}
+
+/// One side of a binary file change
+#[derive(Debug, Clone)]
+pub struct BinarySide {
+    pub size: u64,
+    /// Original width and height, if the content is a decodable image
+    pub dimensions: Option<(u32, u32)>,
+    /// Downscaled image for the preview
+    pub image: Option<Arc<RgbaImage>>,
+}
+
+/// Preview of a binary file change: before/after images, or a size change
+/// with a short hex dump and the printable strings of the content
+#[derive(Debug, Clone)]
+pub struct BinaryChange {
+    /// None if the file did not exist before the change
+    pub old: Option<BinarySide>,
+    /// None if the file was deleted
+    pub new: Option<BinarySide>,
+    pub hex_dump: Vec<String>,
+    pub strings: Vec<String>,
+}
+
+impl BinaryChange {
+    /// Builds the preview of a change from the contents of both sides.
+    ///
+    /// Sides are `(size, content)`, where content is missing if it was too
+    /// large to read.
+    pub fn new(
+        path: &str,
+        old: Option<(u64, Option<&[u8]>)>,
+        new: Option<(u64, Option<&[u8]>)>,
+    ) -> Self {
+        let side = |(size, bytes): (u64, Option<&[u8]>)| {
+            let image = bytes.and_then(|bytes| decode_image(path, bytes));
+            BinarySide {
+                size,
+                dimensions: image.as_ref().map(|(dimensions, _)| *dimensions),
+                image: image.map(|(_, image)| Arc::new(image)),
+            }
+        };
+
+        let shown = new.or(old).and_then(|(_, bytes)| bytes).unwrap_or_default();
+        Self {
+            old: old.map(side),
+            new: new.map(side),
+            hex_dump: hex_dump(shown),
+            strings: printable_strings(shown),
+        }
+    }
+
+    /// Returns true if either side decoded as an image.
+    pub fn is_image(&self) -> bool {
+        [&self.old, &self.new]
+            .into_iter()
+            .flatten()
+            .any(|side| side.image.is_some())
+    }
+
+    /// Describes the size change, e.g. "1.2 KB → 1.5 KB (+307 B)".
+    pub fn size_change(&self) -> String {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => {
+                let delta = new.size as i64 - old.size as i64;
+                let sign = if delta < 0 { "-" } else { "+" };
+                format!(
+                    "{} → {} ({}{})",
+                    format_size(old.size),
+                    format_size(new.size),
+                    sign,
+                    format_size(delta.unsigned_abs())
+                )
+            }
+            (None, Some(new)) => format!("new, {}", format_size(new.size)),
+            (Some(old), None) => format!("deleted, {}", format_size(old.size)),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Describes the dimension change of an image, e.g. "64×64 → 128×128".
+    pub fn dimension_change(&self) -> Option<String> {
+        let show = |side: &Option<BinarySide>| {
+            side.as_ref()
+                .and_then(|side| side.dimensions)
+                .map(|(width, height)| format!("{}×{}", width, height))
+        };
+        match (show(&self.old), show(&self.new)) {
+            (Some(old), Some(new)) if old != new => Some(format!("{} → {}", old, new)),
+            (_, Some(new)) => Some(new),
+            (old, None) => old,
+        }
+    }
+}
+
+/// Returns true if files at `path` are previewed as images, including SVG
+/// files that are stored as text.
+pub fn is_image_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".svg"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
+/// Human-readable byte size, e.g. "512 B", "1.2 KB", "3.4 MB".
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+// Decode an image and scale it down for the preview, keeping its original size
+fn decode_image(path: &str, bytes: &[u8]) -> Option<((u32, u32), RgbaImage)> {
+    if !is_image_path(path) || bytes.is_empty() {
+        return None;
+    }
+
+    if path.to_lowercase().ends_with(".svg") {
+        return rasterize_svg(bytes);
+    }
+
+    let image = image::load_from_memory(bytes).ok()?;
+    let dimensions = (image.width(), image.height());
+    let image = if dimensions.0 > MAX_THUMBNAIL_SIZE || dimensions.1 > MAX_THUMBNAIL_SIZE {
+        image.resize(MAX_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE, FilterType::Triangle)
+    } else {
+        image
+    };
+    Some((dimensions, image.to_rgba8()))
+}
+
+// Render an SVG so that its larger edge is MAX_THUMBNAIL_SIZE pixels
+fn rasterize_svg(bytes: &[u8]) -> Option<((u32, u32), RgbaImage)> {
+    let tree = usvg::Tree::from_data(bytes, &usvg::Options::default()).ok()?;
+    let size = tree.size();
+    let scale = MAX_THUMBNAIL_SIZE as f32 / size.width().max(size.height());
+    let width = ((size.width() * scale).round() as u32).max(1);
+    let height = ((size.height() * scale).round() as u32).max(1);
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    let image = RgbaImage::from_raw(width, height, pixmap.take_demultiplied())?;
+    let dimensions = (size.width().round() as u32, size.height().round() as u32);
+    Some((dimensions, image))
+}
+
+// Offset, hex bytes and ASCII column of the first bytes, like `xxd`
+fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .take(HEX_DUMP_BYTES / HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {:<width$}  |{}|",
+                row * HEX_DUMP_WIDTH,
+                hex.join(" "),
+                ascii,
+                width = HEX_DUMP_WIDTH * 3 - 1
+            )
+        })
+        .collect()
+}
+
+// Runs of printable ASCII, like `strings`
+fn printable_strings(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .split(|&b| !(b.is_ascii_graphic() || b == b' '))
+        .map(|run| String::from_utf8_lossy(run).trim().to_string())
+        .filter(|run| run.len() >= MIN_STRING_LEN)
+        .take(MAX_STRINGS)
+        .map(|run| {
+            if run.len() > MAX_STRING_WIDTH {
+                format!("{}…", &run[..MAX_STRING_WIDTH])
+            } else {
+                run
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, image::Rgba([255, 0, 0, 255]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_image_change() {
+        let (old, new) = (png(4, 4), png(600, 300));
+        let change = BinaryChange::new(
+            "assets/logo.png",
+            Some((old.len() as u64, Some(&old))),
+            Some((new.len() as u64, Some(&new))),
+        );
+
+        assert!(change.is_image());
+        assert_eq!(change.dimension_change().as_deref(), Some("4×4 → 600×300"));
+        let thumbnail = change.new.unwrap().image.unwrap();
+        assert_eq!(thumbnail.dimensions(), (256, 128));
+    }
+
+    #[test]
+    fn test_svg_is_rasterized() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="16"><rect width="32" height="16" fill="blue"/></svg>"#;
+        let change = BinaryChange::new("icon.svg", None, Some((svg.len() as u64, Some(svg))));
+
+        assert_eq!(change.dimension_change().as_deref(), Some("32×16"));
+        let thumbnail = change.new.unwrap().image.unwrap();
+        assert_eq!(thumbnail.get_pixel(10, 10).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_binary_summary() {
+        let bytes = b"\x7fELF\x02\x01\x01\x00\x00\x00libgitlogue.so\x00\x01GLIBC_2.34\x00";
+        let change = BinaryChange::new(
+            "lib/libgitlogue.so",
+            Some((2048, None)),
+            Some((bytes.len() as u64, Some(bytes))),
+        );
+
+        assert!(!change.is_image());
+        assert_eq!(change.size_change(), "2.0 KB → 37 B (-2.0 KB)");
+        assert_eq!(
+            change.hex_dump[0],
+            "00000000  7f 45 4c 46 02 01 01 00 00 00 6c 69 62 67 69 74  |.ELF......libgit|"
+        );
+        assert_eq!(change.strings, vec!["libgitlogue.so", "GLIBC_2.34"]);
+    }
+}
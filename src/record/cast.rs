@@ -0,0 +1,189 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use serde_json::json;
+use unicode_width::UnicodeWidthStr;
+
+use super::Recorder;
+
+/// Writes rendered frames as an asciicast v2 recording: a JSON header line
+/// followed by one `[time, "o", data]` line per frame, where `data` is the
+/// ANSI output that turns the previous frame into the current one.
+pub struct CastWriter<W: Write> {
+    writer: W,
+    previous: Buffer,
+}
+
+impl<W: Write> CastWriter<W> {
+    /// Writes the header for a terminal of `width` x `height` cells.
+    pub fn new(mut writer: W, width: u16, height: u16) -> Result<Self> {
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": chrono::Utc::now().timestamp(),
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(writer, "{}", header).context("Failed to write recording header")?;
+
+        let mut cast = Self {
+            writer,
+            previous: Buffer::empty(Rect::new(0, 0, width, height)),
+        };
+        // The editor draws its own cursor
+        cast.event(Duration::ZERO, "\x1b[?25l\x1b[2J")?;
+        Ok(cast)
+    }
+
+    fn event(&mut self, time: Duration, data: &str) -> Result<()> {
+        let event = json!([time.as_secs_f64(), "o", data]);
+        writeln!(self.writer, "{}", event).context("Failed to write recording")
+    }
+}
+
+impl<W: Write> Recorder for CastWriter<W> {
+    // Frames identical to the previous one are skipped
+    fn frame(&mut self, time: Duration, buffer: &Buffer) -> Result<()> {
+        let data = ansi_diff(&self.previous, buffer);
+        if !data.is_empty() {
+            self.event(time, &data)?;
+            self.previous = buffer.clone();
+        }
+        Ok(())
+    }
+
+    // Leaves the cursor below the last frame
+    fn finish(mut self: Box<Self>, time: Duration) -> Result<()> {
+        let height = self.previous.area.height;
+        self.event(time, &format!("\x1b[0m\x1b[{};1H\x1b[?25h", height))?;
+        self.writer.flush().context("Failed to write recording")
+    }
+}
+
+// ANSI escape sequences that redraw the cells of `current` that differ from
+// `previous`
+fn ansi_diff(previous: &Buffer, current: &Buffer) -> String {
+    let mut out = String::new();
+    let mut position = None;
+    let mut style = None;
+
+    for (x, y, cell) in previous.diff(current) {
+        if position != Some((x, y)) {
+            let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+        }
+        let cell_style = (cell.fg, cell.bg, cell.modifier);
+        if style != Some(cell_style) {
+            out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+            style = Some(cell_style);
+        }
+        out.push_str(cell.symbol());
+        position = Some((x + cell.symbol().width().max(1) as u16, y));
+    }
+
+    out
+}
+
+// Select Graphic Rendition sequence for a cell style, starting from a reset
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = color_code(fg, false) {
+        codes.push(code);
+    }
+    if let Some(code) = color_code(bg, true) {
+        codes.push(code);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+// SGR parameters for a colour, or `None` for the terminal default
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(index) => return Some(format!("{};5;{}", base + 8, index)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_ansi_diff() {
+        let area = Rect::new(0, 0, 10, 2);
+        let previous = Buffer::empty(area);
+        let mut current = Buffer::empty(area);
+        current.set_string(0, 0, "ab", Style::default().fg(Color::Rgb(255, 0, 0)));
+        current.set_string(4, 1, "c", Style::default().bg(Color::Blue).bold());
+
+        assert_eq!(
+            ansi_diff(&previous, &current),
+            "\x1b[1;1H\x1b[0;38;2;255;0;0mab\x1b[2;5H\x1b[0;1;44mc"
+        );
+        assert_eq!(ansi_diff(&current, &current), "");
+    }
+
+    #[test]
+    fn test_cast_events() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut frame = Buffer::empty(area);
+        let mut output = Vec::new();
+
+        let mut cast = Box::new(CastWriter::new(&mut output, 4, 1).unwrap());
+        frame.set_string(0, 0, "a", Style::default());
+        cast.frame(Duration::from_millis(500), &frame).unwrap();
+        // Unchanged frames are not recorded
+        cast.frame(Duration::from_millis(600), &frame).unwrap();
+        cast.finish(Duration::from_millis(1500)).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 4);
+        assert_eq!(lines[2], json!([0.5, "o", "\x1b[1;1H\x1b[0ma"]));
+        assert_eq!(lines[3][0], 1.5);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::escape;
+use super::svg::hex;
+use crate::animation::{AnimationEngine, AnimationStep};
+use crate::git::{CommitMetadata, LineEnding};
+use crate::syntax::{HighlightSpan, Highlighter, TokenType};
+use crate::theme::Theme;
+
+const TEMPLATE: &str = include_str!("player.html");
+
+/// Highlighted range of a line: start and end character, and token
+type LineSpan = (usize, usize, TokenType);
+
+/// Everything the HTML player needs to replay one commit
+#[derive(Serialize)]
+pub struct CommitReplay {
+    /// Typing speed until the first file is opened
+    speed: f64,
+    header: Header,
+    /// Commits shown by `ShowSubmoduleCommit` steps
+    submodules: Vec<Header>,
+    steps: Vec<AnimationStep>,
+    /// Files opened by `SwitchFile` steps, keyed by step index
+    files: BTreeMap<usize, FileView>,
+}
+
+#[derive(Serialize)]
+struct Header {
+    hash: String,
+    author: String,
+    date: String,
+    message: String,
+    files: Vec<FileEntry>,
+}
+
+#[derive(Serialize)]
+struct FileEntry {
+    path: String,
+    status: String,
+    binary: bool,
+}
+
+/// Typing speed and per-line highlights of both versions of an opened file
+#[derive(Serialize)]
+struct FileView {
+    speed: f64,
+    old: Vec<Vec<LineSpan>>,
+    new: Vec<Vec<LineSpan>>,
+}
+
+#[derive(Serialize)]
+struct Replay<'a> {
+    theme: BTreeMap<&'static str, String>,
+    commits: &'a [CommitReplay],
+}
+
+impl CommitReplay {
+    /// Captures the steps `engine` generated for `metadata`, which must be
+    /// the commit it last loaded.
+    pub fn new(metadata: &CommitMetadata, engine: &AnimationEngine) -> Self {
+        // Speeds include the scaling that fits the commit into its duration
+        let scale = engine.commit_scale();
+        let mut highlighter = Highlighter::new();
+        let mut files = BTreeMap::new();
+        for (index, step) in engine.steps().iter().enumerate() {
+            if let AnimationStep::SwitchFile {
+                old_content,
+                new_content,
+                path,
+                syntax_path,
+                ..
+            } = step
+            {
+                highlighter.set_language_from_path(syntax_path.as_deref().unwrap_or(path));
+                files.insert(
+                    index,
+                    FileView {
+                        speed: engine.speed_for_file(path) as f64 * scale,
+                        old: line_spans(old_content, &highlighter.highlight(old_content)),
+                        new: line_spans(new_content, &highlighter.highlight(new_content)),
+                    },
+                );
+            }
+        }
+
+        Self {
+            speed: engine.base_speed() as f64 * scale,
+            header: Header::new(metadata),
+            submodules: engine.submodule_commits().iter().map(Header::new).collect(),
+            steps: engine.steps().to_vec(),
+            files,
+        }
+    }
+}
+
+impl Header {
+    fn new(metadata: &CommitMetadata) -> Self {
+        Self {
+            hash: metadata.hash.clone(),
+            author: metadata.author.clone(),
+            date: metadata.date.format("%Y-%m-%d %H:%M:%S").to_string(),
+            message: metadata.message.clone(),
+            files: metadata
+                .changes
+                .iter()
+                .map(|change| FileEntry {
+                    path: change.path.clone(),
+                    status: change.status.as_str().to_string(),
+                    binary: change.binary.is_some(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes `commits` to `output` as a single HTML page with an embedded
+/// player, styled with `theme`. The page needs no network access.
+pub fn write_html(output: &Path, commits: &[CommitReplay], theme: &Theme) -> Result<()> {
+    let html = render(commits, theme)?;
+    std::fs::write(output, html).with_context(|| format!("Failed to write {}", output.display()))
+}
+
+fn render(commits: &[CommitReplay], theme: &Theme) -> Result<String> {
+    let replay = Replay {
+        theme: theme_variables(theme),
+        commits,
+    };
+    // `<` is escaped so that file contents can't close the script element
+    let data = serde_json::to_string(&replay)
+        .context("Failed to serialize replay")?
+        .replace('<', "\\u003c");
+
+    let title = commits
+        .first()
+        .and_then(|commit| commit.header.message.lines().next())
+        .map(|subject| format!("gitlogue: {}", escape(subject)))
+        .unwrap_or_else(|| "gitlogue".to_string());
+    let (head, tail) = TEMPLATE
+        .split_once("{{DATA}}")
+        .context("Player template has no data placeholder")?;
+    Ok(format!(
+        "{}{}{}",
+        head.replace("{{TITLE}}", &title),
+        data,
+        tail
+    ))
+}
+
+// Splits highlights of `content` by line, as character ranges within each line
+fn line_spans(content: &str, spans: &[HighlightSpan]) -> Vec<Vec<LineSpan>> {
+    let mut starts = Vec::new();
+    let mut texts = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        starts.push(offset);
+        texts.push(LineEnding::split(line).0);
+        offset += line.len();
+    }
+
+    let mut lines = vec![Vec::new(); texts.len()];
+    for span in spans {
+        let mut index = starts.partition_point(|&start| start <= span.start);
+        index = index.saturating_sub(1);
+        while index < texts.len() && starts[index] < span.end {
+            let text = texts[index];
+            let from = span.start.saturating_sub(starts[index]).min(text.len());
+            let to = (span.end - starts[index]).min(text.len());
+            if from < to {
+                lines[index].push((
+                    char_index(text, from),
+                    char_index(text, to),
+                    span.token_type,
+                ));
+            }
+            index += 1;
+        }
+    }
+    lines
+}
+
+fn char_index(text: &str, byte: usize) -> usize {
+    text.char_indices().take_while(|(i, _)| *i < byte).count()
+}
+
+// CSS custom properties for the player, named after the theme fields
+fn theme_variables(theme: &Theme) -> BTreeMap<&'static str, String> {
+    [
+        ("background-left", theme.background_left),
+        ("background-right", theme.background_right),
+        ("editor-line-number", theme.editor_line_number),
+        ("editor-line-number-cursor", theme.editor_line_number_cursor),
+        ("editor-separator", theme.editor_separator),
+        ("editor-cursor-char-bg", theme.editor_cursor_char_bg),
+        ("editor-cursor-char-fg", theme.editor_cursor_char_fg),
+        ("editor-cursor-line-bg", theme.editor_cursor_line_bg),
+        ("file-tree-added", theme.file_tree_added),
+        ("file-tree-deleted", theme.file_tree_deleted),
+        ("file-tree-modified", theme.file_tree_modified),
+        ("file-tree-renamed", theme.file_tree_renamed),
+        ("file-tree-current-file-bg", theme.file_tree_current_file_bg),
+        ("file-tree-current-file-fg", theme.file_tree_current_file_fg),
+        ("file-tree-default", theme.file_tree_default),
+        ("terminal-command", theme.terminal_command),
+        ("terminal-output", theme.terminal_output),
+        ("terminal-cursor-bg", theme.terminal_cursor_bg),
+        ("terminal-cursor-fg", theme.terminal_cursor_fg),
+        ("status-hash", theme.status_hash),
+        ("status-author", theme.status_author),
+        ("status-date", theme.status_date),
+        ("status-message", theme.status_message),
+        ("status-no-commit", theme.status_no_commit),
+        ("separator", theme.separator),
+        ("syntax-keyword", theme.syntax_keyword),
+        ("syntax-type", theme.syntax_type),
+        ("syntax-function", theme.syntax_function),
+        ("syntax-variable", theme.syntax_variable),
+        ("syntax-string", theme.syntax_string),
+        ("syntax-number", theme.syntax_number),
+        ("syntax-comment", theme.syntax_comment),
+        ("syntax-operator", theme.syntax_operator),
+        ("syntax-punctuation", theme.syntax_punctuation),
+        ("syntax-constant", theme.syntax_constant),
+        ("syntax-parameter", theme.syntax_parameter),
+        ("syntax-property", theme.syntax_property),
+        ("syntax-label", theme.syntax_label),
+    ]
+    .into_iter()
+    .map(|(name, color)| (name, hex(color).unwrap_or_else(|| "inherit".to_string())))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_metadata;
+
+    #[test]
+    fn test_line_spans() {
+        let content = "let a = \"é\";\r\n/* x\ny */\n";
+        let spans = [
+            HighlightSpan {
+                start: 8,
+                end: 12,
+                token_type: TokenType::String,
+            },
+            HighlightSpan {
+                start: 15,
+                end: 24,
+                token_type: TokenType::Comment,
+            },
+        ];
+        assert_eq!(
+            line_spans(content, &spans),
+            vec![
+                vec![(8, 11, TokenType::String)],
+                vec![(0, 4, TokenType::Comment)],
+                vec![(0, 4, TokenType::Comment)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_embeds_replay() {
+        let metadata = commit_metadata("Close </script> tags", Vec::new());
+        let mut engine = AnimationEngine::new(30);
+        engine.load_commit(&metadata);
+        let commits = [CommitReplay::new(&metadata, &engine)];
+
+        let html = render(&commits, &Theme::default()).unwrap();
+        assert!(html.contains("<title>gitlogue: Close &lt;/script&gt; tags</title>"));
+        assert!(html.contains("\"type\":\"TerminalPrompt\""));
+        assert!(!html.contains("Close </script>"));
+    }
+}
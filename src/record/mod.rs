@@ -0,0 +1,81 @@
+mod cast;
+mod html;
+mod svg;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use ratatui::buffer::Buffer;
+
+pub use cast::CastWriter;
+pub use html::{write_html, CommitReplay};
+pub use svg::SvgWriter;
+
+use crate::theme::Theme;
+
+/// File format of a recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RecordFormat {
+    /// asciicast v2, for asciinema
+    #[default]
+    Cast,
+    /// Self-contained animated SVG
+    Svg,
+    /// Self-contained HTML page with a player
+    Html,
+}
+
+impl RecordFormat {
+    /// Guesses the format from the extension of `path`, defaulting to asciicast.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("svg") => Self::Svg,
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                Self::Html
+            }
+            _ => Self::Cast,
+        }
+    }
+}
+
+/// Destination for the rendered frames of a playback
+pub trait Recorder {
+    /// Records `buffer` as shown at `time` since the start of the recording.
+    fn frame(&mut self, time: Duration, buffer: &Buffer) -> Result<()>;
+
+    /// Ends the recording at `time` and writes out anything still buffered.
+    fn finish(self: Box<Self>, time: Duration) -> Result<()>;
+}
+
+/// Creates `output` and a recorder that writes `format` to it. HTML replays
+/// are made of steps rather than frames, see `write_html`.
+pub fn create(
+    output: &Path,
+    format: RecordFormat,
+    width: u16,
+    height: u16,
+    theme: &Theme,
+) -> Result<Box<dyn Recorder>> {
+    if format == RecordFormat::Html {
+        bail!("HTML replays are not recorded frame by frame");
+    }
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let writer = BufWriter::new(file);
+    Ok(match format {
+        RecordFormat::Cast => Box::new(CastWriter::new(writer, width, height)?),
+        RecordFormat::Svg => Box::new(SvgWriter::new(writer, width, height, theme)),
+        RecordFormat::Html => unreachable!(),
+    })
+}
+
+// Escapes `text` for use in SVG and HTML text or attribute values
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
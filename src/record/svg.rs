@@ -0,0 +1,330 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+use super::{escape, Recorder};
+use crate::theme::Theme;
+
+const FONT_SIZE: u32 = 15;
+const FONT_FAMILY: &str =
+    "ui-monospace, SFMono-Regular, Menlo, Consolas, 'DejaVu Sans Mono', monospace";
+const CELL_WIDTH: u32 = 9;
+const CELL_HEIGHT: u32 = 18;
+/// Distance from the top of a cell to the text baseline
+const BASELINE: u32 = 14;
+/// How long the last frame stays on screen before the animation starts over
+const END_HOLD: Duration = Duration::from_secs(3);
+
+/// Writes rendered frames as a self-contained animated SVG. Each frame only
+/// draws the cells that changed, in a group that a CSS keyframe animation
+/// reveals at the frame's time, over the frames before it.
+pub struct SvgWriter<W: Write> {
+    writer: W,
+    width: u16,
+    height: u16,
+    /// Colours for cells that use the terminal's default colours
+    foreground: String,
+    background: String,
+    previous: Buffer,
+    /// Cells shown from the start
+    base: String,
+    /// Time and cells of each later frame
+    layers: Vec<(Duration, String)>,
+}
+
+impl<W: Write> SvgWriter<W> {
+    /// Creates a writer for a terminal of `width` x `height` cells, using
+    /// the colours of `theme` where cells have none of their own.
+    pub fn new(writer: W, width: u16, height: u16, theme: &Theme) -> Self {
+        let background = [theme.background_right, theme.background_left]
+            .into_iter()
+            .find_map(hex)
+            .unwrap_or_else(|| "#000000".to_string());
+        let foreground = hex(theme.file_tree_default).unwrap_or_else(|| "#d0d0d0".to_string());
+
+        Self {
+            writer,
+            width,
+            height,
+            foreground,
+            background,
+            previous: Buffer::empty(Rect::new(0, 0, width, height)),
+            base: String::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    // SVG elements that draw the cells of `current` that differ from
+    // `previous`, merging neighbouring cells of the same style
+    fn draw(&self, current: &Buffer) -> String {
+        let mut out = String::new();
+        let mut run: Option<Run> = None;
+
+        for (x, y, cell) in self.previous.diff(current) {
+            let style = self.style(cell);
+            let cells = cell.symbol().width().max(1) as u16;
+            if let Some(run) = run.as_mut() {
+                if run.y == y && run.x + run.cells == x && run.style == style {
+                    run.text.push_str(cell.symbol());
+                    run.cells += cells;
+                    continue;
+                }
+                run.write(&mut out);
+            }
+            run = Some(Run {
+                x,
+                y,
+                cells,
+                text: cell.symbol().to_string(),
+                style,
+            });
+        }
+        if let Some(run) = run {
+            run.write(&mut out);
+        }
+
+        out
+    }
+
+    fn style(&self, cell: &Cell) -> CellStyle {
+        let mut fg = hex(cell.fg).unwrap_or_else(|| self.foreground.clone());
+        let mut bg = hex(cell.bg).unwrap_or_else(|| self.background.clone());
+        if cell.modifier.contains(Modifier::REVERSED) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+        CellStyle {
+            fg,
+            bg,
+            modifier: cell.modifier - Modifier::REVERSED,
+        }
+    }
+}
+
+impl<W: Write> Recorder for SvgWriter<W> {
+    // Frames identical to the previous one are skipped
+    fn frame(&mut self, time: Duration, buffer: &Buffer) -> Result<()> {
+        let cells = self.draw(buffer);
+        if cells.is_empty() {
+            return Ok(());
+        }
+        if time.is_zero() {
+            self.base.push_str(&cells);
+        } else {
+            self.layers.push((time, cells));
+        }
+        self.previous = buffer.clone();
+        Ok(())
+    }
+
+    // Frames are only written out here, since their keyframes depend on the
+    // length of the whole animation
+    fn finish(mut self: Box<Self>, time: Duration) -> Result<()> {
+        let width = u32::from(self.width) * CELL_WIDTH;
+        let height = u32::from(self.height) * CELL_HEIGHT;
+        let total = (time + END_HOLD).as_secs_f64();
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"{FONT_FAMILY}\" font-size=\"{FONT_SIZE}\">"
+        );
+        svg.push_str("<style>\ntext { white-space: pre; }\n");
+        let _ = writeln!(
+            svg,
+            ".f {{ opacity: 0; animation: {total:.3}s step-end infinite; }}"
+        );
+        for (index, (time, _)) in self.layers.iter().enumerate() {
+            let percent = time.as_secs_f64() / total * 100.0;
+            let _ = writeln!(
+                svg,
+                "@keyframes k{index} {{ 0% {{ opacity: 0; }} {percent:.4}%, 100% {{ opacity: 1; }} }}"
+            );
+        }
+        svg.push_str("</style>\n");
+        let _ = writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            self.background
+        );
+        let _ = writeln!(svg, "<g>{}</g>", self.base);
+        for (index, (_, cells)) in self.layers.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<g class=\"f\" style=\"animation-name: k{index}\">{cells}</g>"
+            );
+        }
+        svg.push_str("</svg>\n");
+
+        self.writer
+            .write_all(svg.as_bytes())
+            .and_then(|_| self.writer.flush())
+            .context("Failed to write recording")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CellStyle {
+    fg: String,
+    bg: String,
+    modifier: Modifier,
+}
+
+// Neighbouring cells of one row with the same style
+struct Run {
+    x: u16,
+    y: u16,
+    cells: u16,
+    text: String,
+    style: CellStyle,
+}
+
+impl Run {
+    fn write(&self, out: &mut String) {
+        let x = u32::from(self.x) * CELL_WIDTH;
+        let y = u32::from(self.y) * CELL_HEIGHT;
+        let width = u32::from(self.cells) * CELL_WIDTH;
+        let _ = write!(
+            out,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{CELL_HEIGHT}\" fill=\"{}\"/>",
+            self.style.bg
+        );
+
+        let modifier = self.style.modifier;
+        if self.text.trim().is_empty() || modifier.contains(Modifier::HIDDEN) {
+            return;
+        }
+        let _ = write!(
+            out,
+            "<text x=\"{x}\" y=\"{}\" textLength=\"{width}\" fill=\"{}\"",
+            y + BASELINE,
+            self.style.fg
+        );
+        if modifier.contains(Modifier::BOLD) {
+            out.push_str(" font-weight=\"bold\"");
+        }
+        if modifier.contains(Modifier::ITALIC) {
+            out.push_str(" font-style=\"italic\"");
+        }
+        if modifier.contains(Modifier::DIM) {
+            out.push_str(" fill-opacity=\"0.6\"");
+        }
+        match (
+            modifier.contains(Modifier::UNDERLINED),
+            modifier.contains(Modifier::CROSSED_OUT),
+        ) {
+            (true, true) => out.push_str(" text-decoration=\"underline line-through\""),
+            (true, false) => out.push_str(" text-decoration=\"underline\""),
+            (false, true) => out.push_str(" text-decoration=\"line-through\""),
+            (false, false) => {}
+        }
+        let _ = write!(out, ">{}</text>", escape(&self.text));
+    }
+}
+
+// CSS colour for `color`, or `None` for the default colour. Named and
+// indexed colours use the xterm palette.
+pub(super) fn hex(color: Color) -> Option<String> {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+    };
+
+    let (r, g, b) = match index {
+        0..=15 => ANSI[index as usize],
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_svg_frames() {
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 8, 2);
+        let mut frame = Buffer::empty(area);
+        let mut output = Vec::new();
+
+        let mut svg = Box::new(SvgWriter::new(&mut output, 8, 2, &theme));
+        frame.set_string(0, 0, "a<b", Style::default().fg(Color::Rgb(255, 0, 0)));
+        svg.frame(Duration::ZERO, &frame).unwrap();
+        frame.set_string(2, 1, "c", Style::default().fg(Color::Indexed(196)).bold());
+        svg.frame(Duration::from_secs(1), &frame).unwrap();
+        // Unchanged frames are not recorded
+        svg.frame(Duration::from_secs(2), &frame).unwrap();
+        svg.finish(Duration::from_secs(2)).unwrap();
+
+        let svg = String::from_utf8(output).unwrap();
+        assert!(svg.contains("width=\"72\" height=\"36\""));
+        assert!(svg.contains("fill=\"#ff0000\">a&lt;b</text>"));
+        // Shown after 1s of a 2s recording held for 3s
+        assert!(svg.contains("@keyframes k0 { 0% { opacity: 0; } 20.0000%, 100% { opacity: 1; } }"));
+        assert!(svg.contains(
+            "<text x=\"18\" y=\"32\" textLength=\"9\" fill=\"#ff0000\" font-weight=\"bold\">c</text>"
+        ));
+        assert!(!svg.contains("k1"));
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(hex(Color::Reset), None);
+        assert_eq!(hex(Color::Rgb(1, 2, 3)).as_deref(), Some("#010203"));
+        assert_eq!(hex(Color::Indexed(16)).as_deref(), Some("#000000"));
+        assert_eq!(hex(Color::Indexed(231)).as_deref(), Some("#ffffff"));
+        assert_eq!(hex(Color::Indexed(244)).as_deref(), Some("#808080"));
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::atomic_file;
+
+/// Per-repository playback state persisted between sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoState {
+    /// Recently shown commit hashes, oldest first
+    #[serde(default)]
+    pub recent: Vec<String>,
+    /// Remaining commit hashes of the current shuffle permutation, in play order
+    #[serde(default)]
+    pub shuffle_queue: Vec<String>,
+    /// Last commit played in asc/desc order, keyed by order and filters
+    #[serde(default)]
+    pub cursors: BTreeMap<String, PlaybackCursor>,
+}
+
+/// Position of sequential playback, saved after each commit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackCursor {
+    /// Hash of the last commit played
+    pub commit: String,
+    /// Author timestamp of that commit, used if the commit is later rewritten away
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StateFile {
+    #[serde(default)]
+    repos: BTreeMap<String, RepoState>,
+}
+
+/// Playback state file stored at `~/.local/state/gitlogue/state.toml`
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    /// Opens the state store at the default location.
+    pub fn open_default() -> Result<Self> {
+        Ok(Self::at(Self::state_dir()?.join("state.toml")))
+    }
+
+    /// Opens a state store backed by the given file.
+    pub fn at<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn state_dir() -> Result<PathBuf> {
+        let state_dir = dirs::home_dir()
+            .context("Failed to determine home directory")?
+            .join(".local")
+            .join("state")
+            .join("gitlogue");
+
+        fs::create_dir_all(&state_dir).with_context(|| {
+            format!("Failed to create state directory: {}", state_dir.display())
+        })?;
+
+        Ok(state_dir)
+    }
+
+    /// Load the state for a repository, returning defaults if none was saved.
+    pub fn load_repo(&self, key: &str) -> RepoState {
+        self.read()
+            .ok()
+            .and_then(|mut file| file.repos.remove(key))
+            .unwrap_or_default()
+    }
+
+    /// Update the state for a repository, leaving other repositories untouched.
+    ///
+    /// Fails without saving if the state file can't be parsed, so that the
+    /// state of other repositories isn't lost. The file is replaced
+    /// atomically, so an interrupted write leaves the previous state.
+    pub fn update_repo<F>(&self, key: &str, update: F) -> Result<()>
+    where
+        F: FnOnce(&mut RepoState),
+    {
+        let mut file = self.read()?;
+        update(file.repos.entry(key.to_string()).or_default());
+
+        let contents = toml::to_string(&file).context("Failed to serialize state file")?;
+        atomic_file::write(&self.path, &contents)
+            .with_context(|| format!("Failed to save state file: {}", self.path.display()))
+    }
+
+    fn read(&self) -> Result<StateFile> {
+        if !self.path.exists() {
+            return Ok(StateFile::default());
+        }
+
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read state file: {}", self.path.display()))?;
+
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse state file: {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> (PathBuf, StateStore) {
+        let dir = std::env::temp_dir().join(format!("gitlogue-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("state.toml");
+        (dir, StateStore::at(path))
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let (dir, store) = temp_store("state");
+        assert!(store.load_repo("a").recent.is_empty());
+
+        store
+            .update_repo("a", |state| state.recent.push("abc".to_string()))
+            .unwrap();
+        store
+            .update_repo("b", |state| {
+                state.cursors.insert(
+                    "asc".to_string(),
+                    PlaybackCursor {
+                        commit: "def".to_string(),
+                        time: 42,
+                    },
+                );
+            })
+            .unwrap();
+
+        assert_eq!(store.load_repo("a").recent, vec!["abc"]);
+        assert_eq!(store.load_repo("b").cursors["asc"].time, 42);
+        // Only the state file is left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_state_file_is_kept() {
+        let (dir, store) = temp_store("state-corrupt");
+        store
+            .update_repo("a", |state| state.recent.push("abc".to_string()))
+            .unwrap();
+        let path = dir.join("state.toml");
+        let corrupt = fs::read_to_string(&path).unwrap().replace(']', "");
+        fs::write(&path, &corrupt).unwrap();
+
+        assert!(store.load_repo("a").recent.is_empty());
+        assert!(store
+            .update_repo("b", |state| state.recent.push("def".to_string()))
+            .is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), corrupt);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{bail, Context, Result};
+use globset::Glob;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::animation::apply_hunks;
+use crate::git::{CommitMetadata, DiffHunk, FileChange, GitRepository};
+
+/// Script of a presentation, read from a TOML file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Storyboard {
+    /// Theme for the whole presentation
+    pub theme: Option<String>,
+    /// Typing speed in milliseconds per character for scenes without their own
+    pub speed: Option<u64>,
+    /// Repository of scenes without their own, relative to the storyboard
+    pub repo: Option<PathBuf>,
+    #[serde(default, rename = "scene")]
+    pub scenes: Vec<SceneSpec>,
+    /// Directory that repository paths are relative to
+    #[serde(skip)]
+    base: PathBuf,
+}
+
+/// One `[[scene]]` entry of a storyboard
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneSpec {
+    /// Commit or `A..B` range to play; a scene without one only shows its caption
+    pub commit: Option<String>,
+    pub repo: Option<PathBuf>,
+    pub caption: Option<String>,
+    pub speed: Option<u64>,
+    /// Wait for a key once the scene has played (defaults to true)
+    pub wait: Option<bool>,
+    /// Only play files matching this path or glob pattern
+    pub file: Option<String>,
+    /// Only play these hunks of each file, counted from 1
+    #[serde(default)]
+    pub hunks: Vec<usize>,
+}
+
+/// A scene ready to be played
+#[derive(Debug, Clone)]
+pub struct Scene {
+    /// Commits played in order; empty for a caption card
+    pub commits: Vec<CommitMetadata>,
+    pub caption: Option<String>,
+    pub speed: Option<u64>,
+    pub wait: bool,
+}
+
+impl Storyboard {
+    /// Reads the storyboard at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read storyboard: {}", path.display()))?;
+        let mut storyboard: Self = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse storyboard: {}", path.display()))?;
+        storyboard.base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(storyboard)
+    }
+
+    /// Loads the commits of every scene. Scenes without a repository use
+    /// `default_repo`, and repositories are opened once with `open`.
+    pub fn resolve<F>(&self, default_repo: &Path, mut open: F) -> Result<Vec<Scene>>
+    where
+        F: FnMut(&Path) -> Result<GitRepository>,
+    {
+        let mut repos: HashMap<PathBuf, GitRepository> = HashMap::new();
+        let mut scenes = Vec::with_capacity(self.scenes.len());
+
+        for (index, spec) in self.scenes.iter().enumerate() {
+            let number = index + 1;
+            let commits = match &spec.commit {
+                Some(commit) => {
+                    let path = spec
+                        .repo
+                        .as_ref()
+                        .or(self.repo.as_ref())
+                        .map(|repo| self.base.join(repo))
+                        .unwrap_or_else(|| default_repo.to_path_buf());
+                    if !repos.contains_key(&path) {
+                        let repo = open(&path).with_context(|| {
+                            format!("Scene {}: failed to open repository", number)
+                        })?;
+                        repos.insert(path.clone(), repo);
+                    }
+                    let repo = &repos[&path];
+                    let commits = load_commits(repo, commit).with_context(|| {
+                        format!("Scene {}: failed to load '{}'", number, commit)
+                    })?;
+                    focus(commits, spec)
+                        .with_context(|| format!("Scene {}: nothing to play", number))?
+                }
+                None if spec.caption.is_none() => {
+                    bail!("Scene {} has neither a commit nor a caption", number)
+                }
+                None => Vec::new(),
+            };
+
+            scenes.push(Scene {
+                commits,
+                caption: spec.caption.clone(),
+                speed: spec.speed.or(self.speed),
+                wait: spec.wait.unwrap_or(true),
+            });
+        }
+
+        Ok(scenes)
+    }
+}
+
+fn load_commits(repo: &GitRepository, commit: &str) -> Result<Vec<CommitMetadata>> {
+    if !commit.contains("..") {
+        return Ok(vec![repo.get_commit(commit)?]);
+    }
+    repo.set_commit_range(commit)?;
+    repo.commit_hashes()?
+        .iter()
+        .map(|hash| repo.get_commit(hash))
+        .collect()
+}
+
+// Narrows commits to the file and hunks a scene focuses on, dropping commits
+// that no longer change anything
+fn focus(commits: Vec<CommitMetadata>, spec: &SceneSpec) -> Result<Vec<CommitMetadata>> {
+    let matcher = match &spec.file {
+        Some(pattern) => Some(
+            Glob::new(pattern)
+                .with_context(|| format!("Invalid file pattern: {}", pattern))?
+                .compile_matcher(),
+        ),
+        None => None,
+    };
+    if matcher.is_none() && spec.hunks.is_empty() {
+        return Ok(commits);
+    }
+
+    let focused: Vec<CommitMetadata> = commits
+        .into_iter()
+        .filter_map(|mut metadata| {
+            if let Some(matcher) = &matcher {
+                metadata
+                    .changes
+                    .retain(|change| matcher.is_match(&change.path));
+            }
+            if !spec.hunks.is_empty() {
+                metadata
+                    .changes
+                    .retain_mut(|change| keep_hunks(change, &spec.hunks));
+            }
+            (!metadata.changes.is_empty()).then_some(metadata)
+        })
+        .collect();
+
+    if focused.is_empty() {
+        match &spec.file {
+            Some(pattern) => bail!("No changed file matches '{}'", pattern),
+            None => bail!("No commit has the selected hunks"),
+        }
+    }
+    Ok(focused)
+}
+
+// Keeps the hunks numbered in `hunks` and rebuilds the new content so that
+// it only has their changes. Returns false if no hunk of a text change is
+// left.
+fn keep_hunks(change: &mut FileChange, hunks: &[usize]) -> bool {
+    let kept: Vec<DiffHunk> = change
+        .hunks
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| hunks.contains(&(index + 1)))
+        .map(|(_, hunk)| hunk.clone())
+        .collect();
+    if kept.len() == change.hunks.len() {
+        return true;
+    }
+    if kept.is_empty() {
+        return false;
+    }
+
+    if let Some(old_content) = &change.old_content {
+        let kept: Vec<&DiffHunk> = kept.iter().collect();
+        change.new_content = Some(apply_hunks(old_content, &kept));
+    }
+    change.hunks = kept;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_storyboard() {
+        let storyboard: Storyboard = toml::from_str(
+            r#"
+            theme = "dracula"
+            repo = "../service"
+
+            [[scene]]
+            caption = "Where it started"
+
+            [[scene]]
+            commit = "v1.0..v1.1"
+            speed = 15
+            wait = false
+            file = "src/*.rs"
+            hunks = [2]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(storyboard.theme.as_deref(), Some("dracula"));
+        assert_eq!(storyboard.scenes.len(), 2);
+        assert_eq!(storyboard.scenes[0].commit, None);
+        assert_eq!(storyboard.scenes[1].hunks, vec![2]);
+        assert_eq!(storyboard.scenes[1].wait, Some(false));
+
+        let error = toml::from_str::<Storyboard>("[[scene]]\ncomit = \"HEAD\"\n").unwrap_err();
+        assert!(error.to_string().contains("unknown field"));
+
+        let scenes = Storyboard {
+            scenes: vec![SceneSpec::default()],
+            ..Default::default()
+        }
+        .resolve(Path::new("."), |_| unreachable!());
+        assert!(scenes.is_err());
+    }
+}
@@ -2,13 +2,15 @@ pub mod languages;
 
 use crate::theme::Theme;
 use ratatui::style::Color;
+use std::collections::HashSet;
 use std::path::Path;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::{Language, Parser, Query, QueryCursor};
 
 pub use languages::get_language;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TokenType {
     Comment,
     Constant,
@@ -97,6 +99,13 @@ impl Highlighter {
 
     pub fn set_language_from_path(&mut self, path: &str) -> bool {
         if let Some((language, query_source)) = get_language(Path::new(path)) {
+            // Keep the compiled query when the language doesn't change
+            if self.query.is_some()
+                && self.language.as_ref() == Some(&language)
+                && self.query_source.as_deref() == Some(query_source)
+            {
+                return true;
+            }
             if self.parser.set_language(&language).is_ok() {
                 if let Ok(query) = Query::new(&language, query_source) {
                     self.language = Some(language);
@@ -202,6 +211,44 @@ impl Highlighter {
         spans.sort_by_key(|span| span.start);
         spans
     }
+
+    /// Returns the distinct identifiers of `source`, in order of first
+    /// appearance. Strings and comments are skipped.
+    pub fn identifiers(&mut self, source: &str) -> Vec<String> {
+        let mut identifiers = Vec::new();
+        if self.language.is_none() {
+            return identifiers;
+        }
+        let Some(tree) = self.parser.parse(source, None) else {
+            return identifiers;
+        };
+
+        let mut seen = HashSet::new();
+        let mut cursor = tree.walk();
+        loop {
+            let node = cursor.node();
+            // Covers identifier, type_identifier, field_identifier, etc.
+            if node.child_count() == 0 && node.kind().ends_with("identifier") {
+                if let Ok(text) = node.utf8_text(source.as_bytes()) {
+                    if seen.insert(text) {
+                        identifiers.push(text.to_string());
+                    }
+                }
+            }
+
+            if cursor.goto_first_child() || cursor.goto_next_sibling() {
+                continue;
+            }
+            loop {
+                if !cursor.goto_parent() {
+                    return identifiers;
+                }
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+    }
 }
 
 impl Default for Highlighter {
@@ -0,0 +1,122 @@
+//! Fixtures shared by the unit tests of several modules
+
+use git2::Oid;
+
+use crate::git::{CommitMetadata, FileChange, LineChange, LineChangeType, LineEnding};
+
+/// Commit with a fixed hash and author, made of `changes`
+pub(crate) fn commit_metadata(message: &str, changes: Vec<FileChange>) -> CommitMetadata {
+    CommitMetadata {
+        hash: "0123456789abcdef".to_string(),
+        author: "Test User".to_string(),
+        date: chrono::Utc::now(),
+        message: message.to_string(),
+        changes,
+        whitespace_only: false,
+        repository: None,
+    }
+}
+
+/// LF-terminated diff line without line numbers
+pub(crate) fn line(change_type: LineChangeType, content: &str) -> LineChange {
+    LineChange {
+        change_type,
+        content: content.to_string(),
+        line_ending: LineEnding::Lf,
+        old_line_no: None,
+        new_line_no: None,
+    }
+}
+
+/// Temporary git repository, removed again on drop
+pub(crate) struct TestRepo {
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) repo: git2::Repository,
+}
+
+impl Drop for TestRepo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+impl TestRepo {
+    pub(crate) fn new() -> Self {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::time::{SystemTime, UNIX_EPOCH};
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let unique_id = format!(
+            "{}_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        let path = std::env::temp_dir().join(format!("gitlogue_test_{}", unique_id));
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+        Self::init(path)
+    }
+
+    pub(crate) fn init(path: std::path::PathBuf) -> Self {
+        std::fs::create_dir_all(&path).unwrap();
+
+        let repo = git2::Repository::init(&path).unwrap();
+
+        // Configure user for commits
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        Self { path, repo }
+    }
+
+    pub(crate) fn commit_file(&self, name: &str, content: impl AsRef<[u8]>) -> Oid {
+        std::fs::write(self.path.join(name), content).unwrap();
+        let mut index = self.repo.index().unwrap();
+        index.add_path(std::path::Path::new(name)).unwrap();
+        self.commit_index(&mut index, name)
+    }
+
+    // Point a submodule entry at a commit without touching the working tree
+    pub(crate) fn commit_gitlink(&self, name: &str, id: Oid) -> Oid {
+        let mut index = self.repo.index().unwrap();
+        index
+            .add(&git2::IndexEntry {
+                ctime: git2::IndexTime::new(0, 0),
+                mtime: git2::IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o160000,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id,
+                flags: 0,
+                flags_extended: 0,
+                path: name.as_bytes().to_vec(),
+            })
+            .unwrap();
+        self.commit_index(&mut index, name)
+    }
+
+    pub(crate) fn commit_index(&self, index: &mut git2::Index, message: &str) -> Oid {
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = self.repo.find_tree(tree_id).unwrap();
+        let sig = self.repo.signature().unwrap();
+        let parent = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        self.repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+}
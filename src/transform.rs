@@ -0,0 +1,167 @@
+use serde_json::Value;
+
+/// Turns the raw content of a file into the text that is typed out, for
+/// formats whose raw content is unpleasant to watch
+pub trait ContentTransformer: Sync {
+    /// Returns true if this transformer handles the file at `path`.
+    fn matches(&self, path: &str) -> bool;
+
+    /// Converts raw content, or returns `None` if it cannot be parsed.
+    fn transform(&self, content: &str) -> Option<Transformed>;
+}
+
+/// Transformed view of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transformed {
+    pub text: String,
+    /// Extension that selects syntax highlighting for the view (e.g. "py")
+    pub extension: Option<String>,
+}
+
+static TRANSFORMERS: &[&dyn ContentTransformer] = &[&Notebook];
+
+/// Returns the transformer that handles the file at `path`, if any.
+pub fn find(path: &str) -> Option<&'static dyn ContentTransformer> {
+    TRANSFORMERS
+        .iter()
+        .copied()
+        .find(|transformer| transformer.matches(path))
+}
+
+/// Jupyter notebooks shown as cells in the percent format:
+/// code cells as they are and markdown cells commented out, outputs dropped
+pub struct Notebook;
+
+impl ContentTransformer for Notebook {
+    fn matches(&self, path: &str) -> bool {
+        path.ends_with(".ipynb")
+    }
+
+    fn transform(&self, content: &str) -> Option<Transformed> {
+        if content.trim().is_empty() {
+            return Some(Transformed {
+                text: String::new(),
+                extension: None,
+            });
+        }
+
+        let notebook: Value = serde_json::from_str(content).ok()?;
+        let cells = notebook.get("cells")?.as_array()?;
+
+        let language = notebook
+            .pointer("/metadata/kernelspec/language")
+            .or_else(|| notebook.pointer("/metadata/language_info/name"))
+            .and_then(Value::as_str)
+            .unwrap_or("python")
+            .to_lowercase();
+        let extension = notebook
+            .pointer("/metadata/language_info/file_extension")
+            .and_then(Value::as_str)
+            .map(|ext| ext.trim_start_matches('.').to_string())
+            .or_else(|| language_extension(&language).map(String::from));
+        let comment = comment_prefix(&language);
+
+        let mut text = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+
+            let cell_type = cell.get("cell_type").and_then(Value::as_str);
+            let source = cell_source(cell);
+            match cell_type {
+                Some("code") => {
+                    text.push_str(&format!("{} %%\n", comment));
+                    for line in source.lines() {
+                        text.push_str(line);
+                        text.push('\n');
+                    }
+                }
+                Some(kind) => {
+                    text.push_str(&format!("{} %% [{}]\n", comment, kind));
+                    for line in source.lines() {
+                        if line.is_empty() {
+                            text.push_str(comment);
+                        } else {
+                            text.push_str(&format!("{} {}", comment, line));
+                        }
+                        text.push('\n');
+                    }
+                }
+                None => return None,
+            }
+        }
+
+        Some(Transformed { text, extension })
+    }
+}
+
+// Cell source is either a string or a list of lines with their newlines
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(source)) => source.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+fn comment_prefix(language: &str) -> &'static str {
+    match language {
+        "javascript" | "typescript" | "java" | "scala" | "kotlin" | "c" | "c++" | "cpp" | "c#"
+        | "csharp" | "go" | "rust" | "swift" | "dart" => "//",
+        "lua" | "haskell" | "sql" => "--",
+        _ => "#",
+    }
+}
+
+fn language_extension(language: &str) -> Option<&'static str> {
+    match language {
+        "python" => Some("py"),
+        "r" => Some("r"),
+        "julia" => Some("jl"),
+        "javascript" => Some("js"),
+        "typescript" => Some("ts"),
+        "ruby" => Some("rb"),
+        "scala" => Some("scala"),
+        "rust" => Some("rs"),
+        "go" => Some("go"),
+        "bash" | "sh" => Some("sh"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_cells() {
+        let notebook = r##"{
+            "cells": [
+                {"cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "\n", "Intro"]},
+                {"cell_type": "code", "execution_count": 1, "metadata": {},
+                 "outputs": [{"output_type": "stream", "text": ["3\n"]}],
+                 "source": "x = 1\nprint(x + 2)"}
+            ],
+            "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+            "nbformat": 4,
+            "nbformat_minor": 5
+        }"##;
+
+        let transformed = find("analysis/notebook.ipynb")
+            .unwrap()
+            .transform(notebook)
+            .unwrap();
+        assert_eq!(
+            transformed.text,
+            "# %% [markdown]\n# # Title\n#\n# Intro\n\n# %%\nx = 1\nprint(x + 2)\n"
+        );
+        assert_eq!(transformed.extension.as_deref(), Some("py"));
+    }
+
+    #[test]
+    fn test_invalid_notebook() {
+        assert!(Notebook.transform("{not json").is_none());
+        assert!(find("main.py").is_none());
+    }
+}
@@ -0,0 +1,275 @@
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+/// Keys adjacent to each letter and digit on a QWERTY keyboard
+const QWERTY_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// A key pressed by a typing model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+}
+
+/// A key press and the delay that follows it, relative to the typing speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keystroke {
+    pub key: Key,
+    pub delay: f64,
+}
+
+/// Decides which keys are pressed to type a piece of text, and how long each
+/// key press takes. Typing the returned keys must produce exactly `text`.
+pub trait TypingModel: Send {
+    fn keystrokes(&mut self, text: &str) -> Vec<Keystroke>;
+}
+
+/// Built-in typing models
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TypingStyle {
+    /// Typos fixed with backspace, faster within words, pauses after punctuation
+    #[default]
+    Natural,
+    /// Every key takes 70-130% of the typing speed
+    Uniform,
+}
+
+/// Tunable parameters of the typing models
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypingSettings {
+    pub style: TypingStyle,
+    /// Chance that a letter or digit is mistyped as an adjacent key
+    pub typo_rate: f64,
+    /// Delay multiplier after punctuation and at the end of a line
+    pub punctuation_pause: f64,
+    /// Delay multiplier before an opening brace
+    pub brace_pause: f64,
+    /// Complete identifiers already in the file instead of typing them out
+    pub autocomplete: bool,
+}
+
+impl Default for TypingSettings {
+    fn default() -> Self {
+        Self {
+            style: TypingStyle::Natural,
+            typo_rate: 0.02,
+            punctuation_pause: 3.0,
+            brace_pause: 5.0,
+            autocomplete: true,
+        }
+    }
+}
+
+impl TypingSettings {
+    /// Builds the selected model; the same seed always yields the same keystrokes.
+    pub fn build(&self, seed: u64) -> Box<dyn TypingModel> {
+        let rng = StdRng::seed_from_u64(seed);
+        match self.style {
+            TypingStyle::Natural => Box::new(NaturalTyping {
+                settings: self.clone(),
+                rng,
+            }),
+            TypingStyle::Uniform => Box::new(UniformTyping { rng }),
+        }
+    }
+}
+
+/// Every key takes the typing speed with ±30% variation
+pub struct UniformTyping {
+    rng: StdRng,
+}
+
+impl TypingModel for UniformTyping {
+    fn keystrokes(&mut self, text: &str) -> Vec<Keystroke> {
+        text.chars()
+            .map(|ch| Keystroke {
+                key: Key::Char(ch),
+                delay: self.rng.random_range(0.7..=1.3),
+            })
+            .collect()
+    }
+}
+
+/// Types in bursts: quick within a word, slower at word starts, with pauses
+/// after punctuation, at line ends and before opening braces. Occasionally
+/// hits an adjacent key, sometimes types one more character before noticing,
+/// then backspaces and retypes.
+pub struct NaturalTyping {
+    settings: TypingSettings,
+    rng: StdRng,
+}
+
+impl NaturalTyping {
+    fn delay(&mut self, prev: Option<char>, ch: char, next: Option<char>) -> f64 {
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+        let mut delay = if is_word(prev) && is_word(Some(ch)) {
+            self.rng.random_range(0.45..=0.8)
+        } else {
+            self.rng.random_range(0.9..=1.4)
+        };
+        if matches!(ch, ',' | ';' | '.' | ':') || next.is_none() {
+            delay *= self.settings.punctuation_pause;
+        }
+        if next == Some('{') {
+            delay *= self.settings.brace_pause;
+        }
+        delay
+    }
+
+    fn typo_for(&mut self, ch: char) -> Option<char> {
+        if !ch.is_ascii_alphanumeric() || !self.rng.random_bool(self.settings.typo_rate) {
+            return None;
+        }
+        let neighbours = adjacent_keys(ch.to_ascii_lowercase());
+        if neighbours.is_empty() {
+            return None;
+        }
+        let typo = neighbours[self.rng.random_range(0..neighbours.len())];
+        Some(if ch.is_ascii_uppercase() {
+            typo.to_ascii_uppercase()
+        } else {
+            typo
+        })
+    }
+}
+
+impl TypingModel for NaturalTyping {
+    fn keystrokes(&mut self, text: &str) -> Vec<Keystroke> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut keystrokes = Vec::with_capacity(chars.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let prev = i.checked_sub(1).map(|j| chars[j]);
+            let ch = chars[i];
+
+            if let Some(typo) = self.typo_for(ch) {
+                // Sometimes the next character is typed before the mistake is noticed
+                let overrun = usize::from(i + 1 < chars.len() && self.rng.random_bool(0.3));
+                keystrokes.push(Keystroke {
+                    key: Key::Char(typo),
+                    delay: self.rng.random_range(0.5..=0.9),
+                });
+                for &ch in &chars[i + 1..i + 1 + overrun] {
+                    keystrokes.push(Keystroke {
+                        key: Key::Char(ch),
+                        delay: self.rng.random_range(0.5..=0.9),
+                    });
+                }
+                // Noticing the typo takes a moment, backspacing is quick
+                if let Some(last) = keystrokes.last_mut() {
+                    last.delay += self.rng.random_range(3.0..=6.0);
+                }
+                for _ in 0..=overrun {
+                    keystrokes.push(Keystroke {
+                        key: Key::Backspace,
+                        delay: self.rng.random_range(0.6..=1.0),
+                    });
+                }
+            }
+
+            let delay = self.delay(prev, ch, chars.get(i + 1).copied());
+            keystrokes.push(Keystroke {
+                key: Key::Char(ch),
+                delay,
+            });
+            i += 1;
+        }
+
+        keystrokes
+    }
+}
+
+// Letters and digits next to `ch` in its row and the rows above and below
+fn adjacent_keys(ch: char) -> Vec<char> {
+    let Some((row, col)) = QWERTY_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(ch).map(|col| (row, col)))
+    else {
+        return Vec::new();
+    };
+
+    let mut keys = Vec::new();
+    for (r, row_keys) in QWERTY_ROWS
+        .iter()
+        .enumerate()
+        .skip(row.saturating_sub(1))
+        .take(if row == 0 { 2 } else { 3 })
+    {
+        for c in col.saturating_sub(1)..=col + 1 {
+            if let Some(&key) = row_keys.as_bytes().get(c) {
+                if (r, c) != (row, col) {
+                    keys.push(key as char);
+                }
+            }
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Replays keystrokes into a string, applying backspaces
+    fn typed(keystrokes: &[Keystroke]) -> String {
+        let mut text = String::new();
+        for keystroke in keystrokes {
+            match keystroke.key {
+                Key::Char(ch) => text.push(ch),
+                Key::Backspace => {
+                    text.pop();
+                }
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_typos_are_corrected() {
+        let settings = TypingSettings {
+            typo_rate: 0.5,
+            ..TypingSettings::default()
+        };
+        let mut model = settings.build(7);
+        let text = "fn render_frame(area: Rect) -> Result<()> {";
+        let keystrokes = model.keystrokes(text);
+
+        assert!(keystrokes.iter().any(|k| k.key == Key::Backspace));
+        assert_eq!(typed(&keystrokes), text);
+    }
+
+    #[test]
+    fn test_same_seed_same_keystrokes() {
+        let settings = TypingSettings::default();
+        let text = "let value = compute(input);";
+        assert_eq!(
+            settings.build(42).keystrokes(text),
+            settings.build(42).keystrokes(text)
+        );
+    }
+
+    #[test]
+    fn test_rhythm() {
+        let settings = TypingSettings {
+            typo_rate: 0.0,
+            ..TypingSettings::default()
+        };
+        let keystrokes = settings.build(1).keystrokes("ab; c {");
+        let delays: Vec<f64> = keystrokes.iter().map(|k| k.delay).collect();
+
+        // Within a word is faster than after punctuation and before a brace
+        assert!(delays[1] < 1.0);
+        assert!(delays[2] > 2.0);
+        assert!(delays[5] > 4.0);
+    }
+
+    #[test]
+    fn test_adjacent_keys() {
+        assert_eq!(adjacent_keys('a'), vec!['q', 'w', 's', 'z', 'x']);
+        assert!(adjacent_keys('{').is_empty());
+    }
+}
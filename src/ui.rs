@@ -1,4 +1,5 @@
 use std::io;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -9,29 +10,52 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
 use ratatui::{
-    backend::CrosstermBackend,
+    backend::{CrosstermBackend, TestBackend},
     layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Padding, Paragraph},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
     Frame, Terminal,
 };
+use ratatui_image::picker::{Picker, ProtocolType};
 use unicode_width::UnicodeWidthStr;
 
-use crate::animation::{AnimationEngine, SpeedRule, StepMode};
+use crate::animation::{AnimationEngine, CommitDuration, HunkOrder, SpeedRule, StepMode};
+use crate::bookmarks::{Bookmark, Bookmarks};
+use crate::clock::{Clock, ManualClock};
 use crate::git::{CommitMetadata, DiffMode, GitRepository};
-use crate::panes::{EditorPane, FileTreePane, StatusBarPane, TerminalPane};
+use crate::panes::{EditorPane, FileTreePane, Progress, StatusBarPane, TerminalPane};
+use crate::preview::ImageProtocol;
+use crate::record::{self, CommitReplay, RecordFormat};
+use crate::storyboard::Scene;
 use crate::theme::Theme;
+use crate::typing::TypingSettings;
 use crate::PlaybackOrder;
 
+/// Simulated time between engine ticks while recording
+const RECORD_TICK: Duration = Duration::from_millis(1);
+/// Playback rates that `+` and `-` step through
+const PLAYBACK_RATES: [f64; 11] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0, 6.0, 8.0, 16.0];
+
 #[derive(Debug, Clone, PartialEq)]
 enum UIState {
     Playing,
-    WaitingForNext { resume_at: Instant },
+    WaitingForNext {
+        resume_at: Instant,
+    },
     Menu,
     KeyBindings,
     About,
+    /// List of bookmarks
+    Bookmarks,
+    /// Typing the name of a new bookmark
+    BookmarkName(String),
+    /// Waiting for a key before the next scene of a presentation
+    Cue,
     Finished,
 }
 
@@ -41,8 +65,122 @@ enum PlaybackState {
     Paused,
 }
 
+/// How the source of the next commit is chosen when there are several
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SourceRotation {
+    /// Each source until it runs out, in order
+    #[default]
+    InTurn,
+    /// Each source until it runs out, in a new random order each round
+    Shuffled,
+    /// A source picked at random for every commit, in proportion to its weight
+    Weighted,
+}
+
+/// A repository and the commits to play from it
+pub struct Source {
+    pub repo: GitRepository,
+    /// Name shown with its commits, when playing several repositories
+    pub name: Option<String>,
+    /// Chance of being picked by weighted rotation, relative to other sources
+    pub weight: u64,
+    /// Commit or range to play, or the whole (filtered) history if unset
+    pub commit_spec: Option<String>,
+    /// Theme and typing speed while playing this source, instead of the
+    /// ones the UI was created with
+    pub theme: Option<Theme>,
+    pub speed: Option<u64>,
+    /// Whether a single commit has been played this round
+    played: bool,
+}
+
+impl Source {
+    pub fn new(repo: GitRepository, commit_spec: Option<String>) -> Self {
+        Self {
+            repo,
+            name: None,
+            weight: 1,
+            commit_spec,
+            theme: None,
+            speed: None,
+            played: false,
+        }
+    }
+
+    fn is_range_mode(&self) -> bool {
+        self.commit_spec
+            .as_ref()
+            .is_some_and(|spec| spec.contains(".."))
+    }
+
+    /// Fetches the next commit in `order`. A single commit is only played
+    /// once per round.
+    pub fn next_commit(&mut self, order: PlaybackOrder) -> Result<CommitMetadata> {
+        let mut metadata = self.fetch(order)?;
+        metadata.repository = self.name.clone();
+        Ok(metadata)
+    }
+
+    fn get_commit(&self, hash: &str) -> Result<CommitMetadata> {
+        let mut metadata = self.repo.get_commit(hash)?;
+        metadata.repository = self.name.clone();
+        Ok(metadata)
+    }
+
+    fn fetch(&mut self, order: PlaybackOrder) -> Result<CommitMetadata> {
+        if self.is_range_mode() {
+            return self.repo.next_playable(|repo| match order {
+                PlaybackOrder::Random => repo.random_range_commit(),
+                PlaybackOrder::Asc => repo.next_range_commit_asc(),
+                PlaybackOrder::Desc => repo.next_range_commit_desc(),
+                PlaybackOrder::Shuffle => repo.next_range_commit_shuffle(),
+            });
+        }
+
+        if let Some(spec) = &self.commit_spec {
+            if self.played {
+                anyhow::bail!("Commit {} was already played", spec);
+            }
+            self.played = true;
+            return self.repo.get_commit(spec);
+        }
+
+        self.repo.next_playable(|repo| match order {
+            PlaybackOrder::Random => repo.random_commit(),
+            PlaybackOrder::Asc => repo.next_asc_commit(),
+            PlaybackOrder::Desc => repo.next_desc_commit(),
+            PlaybackOrder::Shuffle => repo.next_shuffle_commit(),
+        })
+    }
+
+    // Starts the commits over for the next round
+    fn reset(&mut self) {
+        self.repo.reset_index();
+        self.played = false;
+    }
+}
+
+/// Scenes of a presentation and the position within them
+struct Presentation {
+    scenes: Vec<Scene>,
+    scene: usize,
+    commit: usize,
+}
+
+impl Presentation {
+    fn current(&self) -> &Scene {
+        &self.scenes[self.scene]
+    }
+
+    // Whether to wait for a key once the current commit has played
+    fn waits(&self) -> bool {
+        let scene = self.current();
+        scene.wait && self.commit + 1 >= scene.commits.len()
+    }
+}
+
 /// Main UI controller for the gitlogue terminal interface.
-pub struct UI<'a> {
+pub struct UI {
     state: UIState,
     speed_ms: u64,
     file_tree: FileTreePane,
@@ -50,32 +188,45 @@ pub struct UI<'a> {
     terminal: TerminalPane,
     status_bar: StatusBarPane,
     engine: AnimationEngine,
-    repo: Option<&'a GitRepository>,
+    sources: Vec<Source>,
+    /// Order in which sources are played this round, and the position in it
+    source_order: Vec<usize>,
+    source_position: usize,
+    /// Source the current commit came from
+    source: Option<usize>,
+    rotation: SourceRotation,
+    /// Shuffles and picks sources
+    rng: StdRng,
     should_exit: Arc<AtomicBool>,
     theme: Theme,
+    default_theme: Theme,
     order: PlaybackOrder,
     loop_playback: bool,
-    commit_spec: Option<String>,
-    is_range_mode: bool,
     diff_mode: Option<DiffMode>,
+    image_protocol: ImageProtocol,
     playback_state: PlaybackState,
     history: Vec<CommitMetadata>,
     history_index: Option<usize>,
     menu_index: usize,
     prev_state: Option<Box<UIState>>,
+    bookmarks: Option<Bookmarks>,
+    bookmark_index: usize,
+    /// Why the last bookmark change couldn't be saved, shown in the list
+    bookmark_error: Option<String>,
+    /// Start of an A-B loop marked with `a`
+    loop_start: Option<usize>,
+    presentation: Option<Presentation>,
 }
 
-impl<'a> UI<'a> {
-    /// Creates a new UI instance with the specified configuration.
-    #[allow(clippy::too_many_arguments)]
+impl UI {
+    /// Creates a new UI instance that plays commits from `sources` once the
+    /// first commit is loaded.
     pub fn new(
         speed_ms: u64,
-        repo: Option<&'a GitRepository>,
+        sources: Vec<Source>,
         theme: Theme,
         order: PlaybackOrder,
         loop_playback: bool,
-        commit_spec: Option<String>,
-        is_range_mode: bool,
         speed_rules: Vec<SpeedRule>,
     ) -> Self {
         let should_exit = Arc::new(AtomicBool::new(false));
@@ -88,23 +239,33 @@ impl<'a> UI<'a> {
             state: UIState::Playing,
             speed_ms,
             file_tree: FileTreePane::new(),
-            editor: EditorPane,
+            editor: EditorPane::new(),
             terminal: TerminalPane,
             status_bar: StatusBarPane,
             engine,
-            repo,
+            sources,
+            source_order: Vec::new(),
+            source_position: 0,
+            source: None,
+            rotation: SourceRotation::InTurn,
+            rng: StdRng::from_rng(&mut rand::rng()),
             should_exit,
+            default_theme: theme.clone(),
             theme,
             order,
             loop_playback,
-            commit_spec,
-            is_range_mode,
             diff_mode: None,
+            image_protocol: ImageProtocol::Auto,
             playback_state: PlaybackState::Playing,
             history: Vec::new(),
             history_index: None,
             menu_index: 0,
             prev_state: None,
+            bookmarks: None,
+            bookmark_index: 0,
+            bookmark_error: None,
+            loop_start: None,
+            presentation: None,
         }
     }
 
@@ -113,10 +274,74 @@ impl<'a> UI<'a> {
         self.diff_mode = mode;
     }
 
+    /// Sets how code and commands are typed.
+    pub fn set_typing_settings(&mut self, settings: &TypingSettings) {
+        self.engine.set_typing_settings(settings);
+    }
+
+    /// Sets the generator behind playback timing and shuffling.
+    pub fn set_rng(&mut self, mut rng: StdRng) {
+        self.rng = rng.fork();
+        self.engine.set_rng(rng);
+    }
+
+    /// Sets how the source of each commit is chosen.
+    pub fn set_source_rotation(&mut self, rotation: SourceRotation) {
+        self.rotation = rotation;
+    }
+
+    /// Sets the order in which hunks are edited.
+    pub fn set_hunk_order(&mut self, order: HunkOrder) {
+        self.engine.set_hunk_order(order);
+    }
+
+    /// Sets where bookmarks are listed and saved.
+    pub fn set_bookmarks(&mut self, bookmarks: Bookmarks) {
+        self.bookmarks = Some(bookmarks);
+    }
+
+    /// Fits each commit into `duration`, if set.
+    pub fn set_commit_duration(&mut self, duration: Option<CommitDuration>) {
+        self.engine.set_commit_duration(duration);
+    }
+
+    /// Sets the playback rate that `+` and `-` start from.
+    pub fn set_playback_rate(&mut self, rate: f64) {
+        self.engine.set_playback_rate(rate);
+    }
+
+    /// Sets how image previews are drawn.
+    pub fn set_image_protocol(&mut self, protocol: ImageProtocol) {
+        self.image_protocol = protocol;
+    }
+
+    // Kitty and sixel support is detected by querying the terminal, which has
+    // to happen on the alternate screen before any events are read
+    fn image_picker(&self) -> Picker {
+        let mut picker = match self.image_protocol {
+            ImageProtocol::Halfblocks => Picker::halfblocks(),
+            _ => Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks()),
+        };
+        match self.image_protocol {
+            ImageProtocol::Kitty => picker.set_protocol_type(ProtocolType::Kitty),
+            ImageProtocol::Sixel => picker.set_protocol_type(ProtocolType::Sixel),
+            ImageProtocol::Auto | ImageProtocol::Halfblocks => {}
+        }
+        if let Color::Rgb(r, g, b) = self.theme.background_right {
+            picker.set_background_color([r, g, b, 255]);
+        }
+        picker
+    }
+
     fn open_menu(&mut self) {
-        self.prev_state = Some(Box::new(self.state.clone()));
         self.menu_index = 0;
-        self.state = UIState::Menu;
+        self.open_overlay(UIState::Menu);
+    }
+
+    // Pauses playback behind a menu or dialog until `close_menu`
+    fn open_overlay(&mut self, state: UIState) {
+        self.prev_state = Some(Box::new(self.state.clone()));
+        self.state = state;
         self.engine.pause();
     }
 
@@ -232,7 +457,200 @@ impl<'a> UI<'a> {
         let _ = self.engine.restore_change_checkpoint();
     }
 
+    fn change_rate(&mut self, faster: bool) {
+        let rate = self.engine.playback_rate();
+        let next = if faster {
+            PLAYBACK_RATES.iter().find(|&&r| r > rate)
+        } else {
+            PLAYBACK_RATES.iter().rev().find(|&&r| r < rate)
+        };
+        if let Some(&next) = next {
+            self.engine.set_playback_rate(next);
+        }
+    }
+
+    fn seek_percent(&mut self, percent: isize) {
+        let (current, total) = self.engine.progress();
+        let distance = (total * percent.unsigned_abs() / 100).max(1);
+        let target = if percent < 0 {
+            current.saturating_sub(distance)
+        } else {
+            current + distance
+        };
+        self.engine.seek(target);
+        self.resume_after_seek();
+    }
+
+    fn seek_file(&mut self, forward: bool) {
+        if self.engine.seek_file(forward) {
+            self.resume_after_seek();
+        }
+    }
+
+    // Seeking back from the end of a commit keeps playing it
+    fn resume_after_seek(&mut self) {
+        if matches!(self.state, UIState::WaitingForNext { .. }) && !self.engine.is_finished() {
+            self.state = UIState::Playing;
+        }
+    }
+
+    // `a` marks the start of a loop and `b` its end, in either order
+    fn mark_loop(&mut self, end: bool) {
+        let (step, _) = self.engine.progress();
+        if !end {
+            self.loop_start = Some(step);
+            self.engine.set_loop(None);
+            return;
+        }
+        if let Some(start) = self.loop_start {
+            self.engine.set_loop(Some(start.min(step)..start.max(step)));
+            if let Some(range) = self.engine.loop_range() {
+                self.engine.seek(range.start);
+                self.resume_after_seek();
+            }
+        }
+    }
+
+    fn clear_loop(&mut self) {
+        self.loop_start = None;
+        self.engine.set_loop(None);
+    }
+
+    // Commit being played, as loaded; the engine only shows its metadata
+    // after the intro
+    fn current_commit(&self) -> Option<&CommitMetadata> {
+        self.history_index.and_then(|index| self.history.get(index))
+    }
+
+    fn add_bookmark(&mut self, name: &str) {
+        let Some(commit) = self.current_commit().map(|meta| meta.hash.clone()) else {
+            return;
+        };
+        let (step, _) = self.engine.progress();
+        let name = match name.trim() {
+            "" => format!("{} @ {}", &commit[..7.min(commit.len())], step),
+            name => name.to_string(),
+        };
+        let Some(bookmarks) = self.bookmarks.as_mut() else {
+            return;
+        };
+        let result = bookmarks.add(Bookmark {
+            name,
+            commit,
+            step,
+            anchor: self.engine.anchor(step),
+        });
+        self.bookmark_error = result.err().map(bookmark_error);
+        // The name prompt is gone by now, so a failed save opens the list
+        if self.bookmark_error.is_some() {
+            self.bookmark_index = 0;
+            self.open_overlay(UIState::Bookmarks);
+        }
+    }
+
+    fn delete_bookmark(&mut self) {
+        if let Some(bookmarks) = self.bookmarks.as_mut() {
+            let result = bookmarks.remove(self.bookmark_index);
+            self.bookmark_error = result.err().map(bookmark_error);
+            self.bookmark_index = self
+                .bookmark_index
+                .min(bookmarks.items().len().saturating_sub(1));
+        }
+    }
+
+    // Opens the bookmark under the cursor, loading its commit from the
+    // history or the repository if another commit is playing
+    fn jump_to_bookmark(&mut self) {
+        let Some(bookmark) = self
+            .bookmarks
+            .as_ref()
+            .and_then(|bookmarks| bookmarks.items().get(self.bookmark_index))
+            .cloned()
+        else {
+            return;
+        };
+
+        self.close_menu();
+        if self.current_commit().map(|meta| meta.hash.as_str()) != Some(&bookmark.commit) {
+            if let Some(index) = self.history.iter().position(|m| m.hash == bookmark.commit) {
+                self.play_history_commit(index);
+            } else if let Some(metadata) = self
+                .sources
+                .iter()
+                .find_map(|source| source.get_commit(&bookmark.commit).ok())
+            {
+                self.load_commit(metadata);
+            } else {
+                return;
+            }
+        }
+        let step = bookmark
+            .anchor
+            .and_then(|anchor| self.engine.anchor_step(&anchor))
+            .unwrap_or(bookmark.step);
+        self.ensure_manual_pause();
+        self.engine.seek(step);
+        self.resume_after_seek();
+    }
+
+    /// Plays `scenes` in order, waiting for a key after each scene that
+    /// asks for it.
+    pub fn present(&mut self, scenes: Vec<Scene>) -> Result<()> {
+        if scenes.is_empty() {
+            anyhow::bail!("Storyboard has no scenes");
+        }
+        self.presentation = Some(Presentation {
+            scenes,
+            scene: 0,
+            commit: 0,
+        });
+        self.start_scene(0);
+        self.run()
+    }
+
+    fn start_scene(&mut self, index: usize) {
+        let Some(presentation) = self.presentation.as_mut() else {
+            return;
+        };
+        presentation.scene = index;
+        presentation.commit = 0;
+        let scene = presentation.current();
+        let speed = scene.speed.unwrap_or(self.speed_ms);
+        let first = scene.commits.first().cloned();
+
+        self.clear_loop();
+        self.engine.set_base_speed(speed);
+        match first {
+            Some(metadata) => self.load_commit(metadata),
+            // Caption cards are shown over the last commit played
+            None => self.cue(),
+        }
+    }
+
+    fn next_scene(&mut self) {
+        match &self.presentation {
+            Some(p) if p.scene + 1 < p.scenes.len() => self.start_scene(p.scene + 1),
+            Some(_) => self.state = UIState::Finished,
+            None => {}
+        }
+    }
+
+    fn prev_scene(&mut self) {
+        if let Some(scene) = self.presentation.as_ref().map(|p| p.scene) {
+            self.start_scene(scene.saturating_sub(1));
+        }
+    }
+
+    fn cue(&mut self) {
+        self.state = UIState::Cue;
+        self.engine.pause();
+    }
+
     fn handle_prev(&mut self) {
+        if self.presentation.is_some() {
+            self.prev_scene();
+            return;
+        }
         if let Some(index) = self.history_index {
             if index > 0 {
                 let target = index - 1;
@@ -242,6 +660,10 @@ impl<'a> UI<'a> {
     }
 
     fn handle_next(&mut self) {
+        if self.presentation.is_some() {
+            self.next_scene();
+            return;
+        }
         if let Some(index) = self.history_index {
             if index + 1 < self.history.len() {
                 let target = index + 1;
@@ -251,7 +673,7 @@ impl<'a> UI<'a> {
             }
         }
 
-        if self.repo.is_none() && self.diff_mode.is_none() {
+        if self.sources.is_empty() && self.diff_mode.is_none() {
             return;
         }
 
@@ -259,9 +681,20 @@ impl<'a> UI<'a> {
     }
 
     fn advance_to_next_commit(&mut self) -> bool {
+        if let Some(presentation) = self.presentation.as_mut() {
+            let scene = &presentation.scenes[presentation.scene];
+            if let Some(metadata) = scene.commits.get(presentation.commit + 1).cloned() {
+                presentation.commit += 1;
+                self.load_commit(metadata);
+            } else {
+                self.next_scene();
+            }
+            return self.state != UIState::Finished;
+        }
+
         if let Some(diff_mode) = self.diff_mode {
-            if let Some(repo) = self.repo {
-                match repo.get_working_tree_diff(diff_mode) {
+            if let Some(source) = self.sources.first() {
+                match source.repo.get_working_tree_diff(diff_mode) {
                     Ok(metadata) if !metadata.changes.is_empty() => {
                         self.load_commit(metadata);
                         return true;
@@ -276,52 +709,100 @@ impl<'a> UI<'a> {
             return false;
         }
 
-        let Some(repo) = self.repo else {
-            self.state = UIState::Finished;
-            return false;
-        };
-
-        match self.fetch_repo_commit(repo) {
-            Ok(metadata) => {
+        match self.next_commit() {
+            Some(metadata) => {
                 self.load_commit(metadata);
                 true
             }
-            Err(_) => {
-                if self.loop_playback {
-                    repo.reset_index();
-                    if let Ok(metadata) = self.fetch_repo_commit(repo) {
-                        self.load_commit(metadata);
-                        true
-                    } else {
-                        self.state = UIState::Finished;
-                        false
-                    }
-                } else {
-                    self.state = UIState::Finished;
-                    false
-                }
+            None => {
+                self.state = UIState::Finished;
+                false
             }
         }
     }
 
-    fn fetch_repo_commit(&self, repo: &GitRepository) -> Result<CommitMetadata> {
-        if self.is_range_mode {
-            return match self.order {
-                PlaybackOrder::Random => repo.random_range_commit(),
-                PlaybackOrder::Asc => repo.next_range_commit_asc(),
-                PlaybackOrder::Desc => repo.next_range_commit_desc(),
-            };
+    /// Fetches the next commit from the sources, moving on to the next
+    /// source once one runs out and starting a new round at the end if
+    /// looping.
+    pub fn next_commit(&mut self) -> Option<CommitMetadata> {
+        if self.rotation == SourceRotation::Weighted {
+            return self.next_weighted_commit();
+        }
+        if self.source_order.is_empty() {
+            self.start_round();
         }
 
-        if let Some(spec) = &self.commit_spec {
-            return repo.get_commit(spec);
+        // Every source gets one try this round and one after starting over
+        for _ in 0..self.sources.len() * 2 {
+            let index = self.source_order[self.source_position];
+            if let Ok(metadata) = self.sources[index].next_commit(self.order) {
+                self.use_source(index);
+                return Some(metadata);
+            }
+
+            if self.source_position + 1 < self.source_order.len() {
+                self.source_position += 1;
+            } else if self.loop_playback {
+                self.sources.iter_mut().for_each(Source::reset);
+                self.start_round();
+            } else {
+                return None;
+            }
         }
+        None
+    }
 
-        match self.order {
-            PlaybackOrder::Random => repo.random_commit(),
-            PlaybackOrder::Asc => repo.next_asc_commit(),
-            PlaybackOrder::Desc => repo.next_desc_commit(),
+    // Sources that run out start over if looping, and are otherwise left
+    // out of later picks
+    fn next_weighted_commit(&mut self) -> Option<CommitMetadata> {
+        for _ in 0..self.sources.len() * 2 {
+            let total: u64 = self.sources.iter().map(|source| source.weight).sum();
+            if total == 0 {
+                return None;
+            }
+            let mut pick = self.rng.random_range(0..total);
+            let index = self.sources.iter().position(|source| {
+                if pick < source.weight {
+                    return true;
+                }
+                pick -= source.weight;
+                false
+            })?;
+
+            if let Ok(metadata) = self.sources[index].next_commit(self.order) {
+                self.use_source(index);
+                return Some(metadata);
+            }
+            if self.loop_playback {
+                self.sources[index].reset();
+            } else {
+                self.sources[index].weight = 0;
+            }
         }
+        None
+    }
+
+    fn start_round(&mut self) {
+        self.source_order = (0..self.sources.len()).collect();
+        if self.rotation == SourceRotation::Shuffled {
+            self.source_order.shuffle(&mut self.rng);
+        }
+        self.source_position = 0;
+    }
+
+    // Switches to the theme and speed of the source at `index`
+    fn use_source(&mut self, index: usize) {
+        if self.source == Some(index) {
+            return;
+        }
+        self.source = Some(index);
+        let source = &self.sources[index];
+        self.theme = source
+            .theme
+            .clone()
+            .unwrap_or_else(|| self.default_theme.clone());
+        self.engine
+            .set_base_speed(source.speed.unwrap_or(self.speed_ms));
     }
 
     /// Runs the main UI event loop.
@@ -329,6 +810,7 @@ impl<'a> UI<'a> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        self.editor.set_picker(self.image_picker());
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
@@ -359,12 +841,7 @@ impl<'a> UI<'a> {
 
             // Update viewport dimensions for scroll calculation
             let size = terminal.size()?;
-            // Editor area: 70% (right column) × 80% (editor pane) = 56% of total height
-            let viewport_height = (size.height as f32 * 0.70 * 0.80) as usize;
-            // Editor width: 70% (right column)
-            let content_width = (size.width as f32 * 0.70) as usize;
-            self.engine.set_viewport_height(viewport_height);
-            self.engine.set_content_width(content_width);
+            self.set_viewport(size.width, size.height);
 
             // Tick the animation engine
             let needs_redraw = self.engine.tick();
@@ -392,6 +869,52 @@ impl<'a> UI<'a> {
                             },
                             _ => {}
                         },
+                        UIState::Bookmarks => match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') => self.close_menu(),
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                self.bookmark_index = self.bookmark_index.saturating_sub(1);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                let count = self.bookmarks.as_ref().map_or(0, |b| b.items().len());
+                                self.bookmark_index =
+                                    (self.bookmark_index + 1).min(count.saturating_sub(1));
+                            }
+                            KeyCode::Enter => self.jump_to_bookmark(),
+                            KeyCode::Char('d') => self.delete_bookmark(),
+                            _ => {}
+                        },
+                        UIState::BookmarkName(name) => match key.code {
+                            KeyCode::Esc => self.close_menu(),
+                            KeyCode::Enter => {
+                                let name = name.clone();
+                                self.close_menu();
+                                self.add_bookmark(&name);
+                            }
+                            KeyCode::Backspace => {
+                                let mut name = name.clone();
+                                name.pop();
+                                self.state = UIState::BookmarkName(name);
+                            }
+                            KeyCode::Char(ch) => {
+                                self.state = UIState::BookmarkName(format!("{name}{ch}"));
+                            }
+                            _ => {}
+                        },
+                        UIState::Cue => match key.code {
+                            KeyCode::Esc => self.open_menu(),
+                            KeyCode::Char('q') => {
+                                self.state = UIState::Finished;
+                            }
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                self.state = UIState::Finished;
+                            }
+                            KeyCode::Char(' ')
+                            | KeyCode::Enter
+                            | KeyCode::Right
+                            | KeyCode::Char('n') => self.next_scene(),
+                            KeyCode::Left | KeyCode::Char('p') => self.prev_scene(),
+                            _ => {}
+                        },
                         UIState::KeyBindings | UIState::About => match key.code {
                             KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
                                 self.state = UIState::Menu;
@@ -425,6 +948,26 @@ impl<'a> UI<'a> {
                                 'L' => self.step_change(),
                                 'p' => self.handle_prev(),
                                 'n' => self.handle_next(),
+                                '[' => self.seek_percent(-10),
+                                ']' => self.seek_percent(10),
+                                '{' => self.seek_file(false),
+                                '}' => self.seek_file(true),
+                                '+' | '=' => self.change_rate(true),
+                                '-' => self.change_rate(false),
+                                'f' => {
+                                    let _ = self.engine.fast_forward_file();
+                                }
+                                'a' => self.mark_loop(false),
+                                'b' => self.mark_loop(true),
+                                'c' => self.clear_loop(),
+                                'm' if self.bookmarks.is_some() => {
+                                    self.open_overlay(UIState::BookmarkName(String::new()));
+                                }
+                                'B' if self.bookmarks.is_some() => {
+                                    self.bookmark_index = 0;
+                                    self.bookmark_error = None;
+                                    self.open_overlay(UIState::Bookmarks);
+                                }
                                 _ => {}
                             },
                             _ => {}
@@ -433,39 +976,123 @@ impl<'a> UI<'a> {
                 }
             }
 
-            // State machine
-            match self.state {
-                UIState::Playing => {
-                    if self.engine.is_finished() {
-                        if self.repo.is_some() {
-                            self.state = UIState::WaitingForNext {
-                                resume_at: Instant::now()
-                                    + Duration::from_millis(self.speed_ms * 100),
-                            };
-                        } else {
-                            self.state = UIState::Finished;
-                        }
-                    }
-                }
-                UIState::WaitingForNext { resume_at } => {
-                    if Instant::now() >= resume_at {
-                        if matches!(self.playback_state, PlaybackState::Paused) {
-                            continue;
-                        }
+            self.update_state(Instant::now());
+            if self.state == UIState::Finished {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Plays `metadata`, and the commits that follow it, on simulated time
+    /// and records every frame to `output` in `format`, on a terminal of
+    /// `width` x `height` cells at up to `fps` frames per second.
+    pub fn record(
+        &mut self,
+        metadata: CommitMetadata,
+        output: &Path,
+        format: RecordFormat,
+        width: u16,
+        height: u16,
+        fps: u32,
+    ) -> Result<()> {
+        if format == RecordFormat::Html {
+            return self.export_html(metadata, output);
+        }
+
+        let clock = ManualClock::new();
+        self.engine.set_clock(Box::new(clock.clone()));
+        // Terminal graphics protocols can't be replayed from a recording
+        self.image_protocol = ImageProtocol::Halfblocks;
+        self.editor.set_picker(self.image_picker());
+        self.load_commit(metadata);
+
+        let mut recorder = record::create(output, format, width, height, &self.theme)?;
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        self.set_viewport(width, height);
+
+        let frame_interval = Duration::from_secs(1) / fps.max(1);
+        let mut next_frame = Duration::ZERO;
+        let mut dirty = true;
+        loop {
+            dirty |= self.engine.tick();
+            if dirty && clock.elapsed() >= next_frame {
+                terminal.draw(|f| self.render(f))?;
+                recorder.frame(clock.elapsed(), terminal.backend().buffer())?;
+                dirty = false;
+                next_frame = clock.elapsed() + frame_interval;
+            }
 
-                        self.advance_to_next_commit();
+            self.update_state(clock.now());
+            if self.state == UIState::Finished {
+                break;
+            }
+            clock.advance(RECORD_TICK);
+        }
+
+        if dirty {
+            terminal.draw(|f| self.render(f))?;
+            recorder.frame(clock.elapsed(), terminal.backend().buffer())?;
+        }
+        recorder.finish(clock.elapsed())
+    }
+
+    // Steps are captured without playing them; the page's player replays
+    // them with its own timing
+    fn export_html(&mut self, metadata: CommitMetadata, output: &Path) -> Result<()> {
+        let mut commits = Vec::new();
+        let mut next = Some(metadata);
+        while let Some(metadata) = next {
+            self.engine.load_commit(&metadata);
+            commits.push(CommitReplay::new(&metadata, &self.engine));
+            next = self.next_commit();
+        }
+        record::write_html(output, &commits, &self.theme)
+    }
+
+    fn set_viewport(&mut self, width: u16, height: u16) {
+        // Editor area: 70% (right column) × 80% (editor pane) = 56% of total height
+        let viewport_height = (height as f32 * 0.70 * 0.80) as usize;
+        // Editor width: 70% (right column)
+        let content_width = (width as f32 * 0.70) as usize;
+        self.engine.set_viewport_height(viewport_height);
+        self.engine.set_content_width(content_width);
+    }
+
+    // Moves on once the current commit has finished playing
+    fn update_state(&mut self, now: Instant) {
+        match self.state {
+            UIState::Playing => {
+                if self.engine.is_finished() {
+                    if self.presentation.as_ref().is_some_and(Presentation::waits) {
+                        self.cue();
+                    } else if !self.sources.is_empty() || self.presentation.is_some() {
+                        self.state = UIState::WaitingForNext {
+                            resume_at: now
+                                + Duration::from_millis(self.speed_ms * 100)
+                                    .div_f64(self.engine.playback_rate()),
+                        };
+                    } else {
+                        self.state = UIState::Finished;
                     }
                 }
-                UIState::Menu | UIState::KeyBindings | UIState::About => {
-                    // Paused while in menu/dialog
-                }
-                UIState::Finished => {
-                    break;
+            }
+            UIState::WaitingForNext { resume_at } => {
+                if now >= resume_at && self.playback_state == PlaybackState::Playing {
+                    self.advance_to_next_commit();
                 }
             }
+            UIState::Menu
+            | UIState::KeyBindings
+            | UIState::About
+            | UIState::Bookmarks
+            | UIState::BookmarkName(_)
+            | UIState::Cue
+            | UIState::Finished => {
+                // Paused while in menu/dialog
+            }
         }
-
-        Ok(())
     }
 
     fn render(&mut self, f: &mut Frame) {
@@ -529,10 +1156,18 @@ impl<'a> UI<'a> {
         f.render_widget(left_sep, left_layout[1]);
 
         // Render commit info
+        let (step, total) = self.engine.progress();
+        let progress = Progress {
+            step,
+            total,
+            rate: self.engine.effective_rate(),
+            loop_range: self.engine.loop_range(),
+        };
         self.status_bar.render(
             f,
             left_layout[2],
             self.engine.current_metadata(),
+            &progress,
             &self.theme,
         );
 
@@ -553,6 +1188,10 @@ impl<'a> UI<'a> {
         self.terminal
             .render(f, right_layout[2], &self.engine, &self.theme);
 
+        if self.presentation.is_some() {
+            self.render_caption(f, size, right_layout[0]);
+        }
+
         // Render dialog if present
         if let Some(ref title) = self.engine.dialog_title {
             let text = &self.engine.dialog_typing_text;
@@ -601,14 +1240,88 @@ impl<'a> UI<'a> {
         }
 
         // Render menu / key bindings / about overlays
-        match self.state {
+        match &self.state {
             UIState::Menu => self.render_menu(f, size),
             UIState::KeyBindings => self.render_keybindings(f, size),
             UIState::About => self.render_about(f, size),
+            UIState::Bookmarks => self.render_bookmarks(f, size),
+            UIState::BookmarkName(name) => self.render_bookmark_name(f, size, name),
             _ => {}
         }
     }
 
+    // Caption of the current scene, over the bottom of the editor, or in the
+    // middle of the screen for a caption card
+    fn render_caption(&self, f: &mut Frame, size: Rect, editor: Rect) {
+        let Some(presentation) = &self.presentation else {
+            return;
+        };
+        let scene = presentation.current();
+        let waiting = self.state == UIState::Cue;
+        if scene.caption.is_none() && !waiting {
+            return;
+        }
+        let caption = scene.caption.as_deref().unwrap_or("");
+
+        let card = scene.commits.is_empty();
+        let width = if card {
+            (size.width * 2 / 3).max(40).min(size.width)
+        } else {
+            editor.width.saturating_sub(4)
+        };
+        // Borders and padding take 4 columns
+        let content_width = width.saturating_sub(4).max(1) as usize;
+        let lines: u16 = caption
+            .lines()
+            .map(|line| line.width().max(1).div_ceil(content_width) as u16)
+            .sum();
+        let height = lines + if card { 4 } else { 2 };
+
+        let area = if card {
+            Self::centered_rect(size, width, height)
+        } else {
+            let height = height.min(editor.height);
+            Rect {
+                x: editor.x + (editor.width - width) / 2,
+                y: editor.y + editor.height - height,
+                width,
+                height,
+            }
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " {}/{} ",
+                presentation.scene + 1,
+                presentation.scenes.len()
+            ))
+            .padding(if card {
+                Padding::new(1, 1, 1, 1)
+            } else {
+                Padding::horizontal(1)
+            })
+            .style(
+                Style::default()
+                    .fg(self.theme.status_message)
+                    .bg(self.theme.editor_cursor_line_bg),
+            );
+        if waiting {
+            block = block.title_bottom(Span::styled(
+                " Space to continue ",
+                Style::default().fg(self.theme.status_date),
+            ));
+        }
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(caption)
+                .wrap(Wrap { trim: false })
+                .block(block),
+            area,
+        );
+    }
+
     fn render_menu(&self, f: &mut Frame, size: Rect) {
         let items = ["Key Bindings", "About", "Exit"];
         let lines: Vec<Line> = items
@@ -661,6 +1374,14 @@ impl<'a> UI<'a> {
             Line::from("  h / l   Step line back / forward"),
             Line::from("  H / L   Step change back / forward"),
             Line::from("  p / n   Previous / Next commit"),
+            Line::from("  [ / ]   Seek back / forward 10%"),
+            Line::from("  { / }   Previous / Next file"),
+            Line::from("  + / -   Faster / Slower"),
+            Line::from("  f       Fast-forward this file"),
+            Line::from("  a / b   Loop from A to B"),
+            Line::from("  c       Clear loop"),
+            Line::from("  m       Add bookmark"),
+            Line::from("  B       Bookmarks"),
         ];
 
         let block = Block::default()
@@ -680,6 +1401,101 @@ impl<'a> UI<'a> {
         f.render_widget(Paragraph::new(lines).block(block), area);
     }
 
+    fn render_bookmarks(&self, f: &mut Frame, size: Rect) {
+        let items = self.bookmarks.as_ref().map_or(&[][..], |b| b.items());
+        let current = self.current_commit().map(|meta| meta.hash.as_str());
+        let mut lines: Vec<Line> = items
+            .iter()
+            .enumerate()
+            .map(|(i, bookmark)| {
+                let marker = if i == self.bookmark_index { "> " } else { "  " };
+                let style = if i == self.bookmark_index {
+                    Style::default().fg(self.theme.file_tree_current_file_fg)
+                } else {
+                    Style::default().fg(self.theme.status_message)
+                };
+                let commit = &bookmark.commit[..7.min(bookmark.commit.len())];
+                let here = if current == Some(bookmark.commit.as_str()) {
+                    " *"
+                } else {
+                    ""
+                };
+                Line::from(vec![
+                    Span::styled(format!("{marker}{}", bookmark.name), style),
+                    Span::styled(
+                        format!("  {commit} @ {}{here}", bookmark.step),
+                        Style::default().fg(self.theme.status_hash),
+                    ),
+                ])
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("No bookmarks yet, press m to add one"));
+        }
+        if let Some(error) = &self.bookmark_error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(self.theme.file_tree_deleted),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Enter open   d delete",
+            Style::default().fg(self.theme.status_date),
+        )));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Bookmarks (Esc to close) ")
+            .padding(Padding::new(2, 2, 1, 1))
+            .style(
+                Style::default()
+                    .fg(self.theme.status_message)
+                    .bg(self.theme.editor_cursor_line_bg),
+            );
+
+        let dialog_width = lines
+            .iter()
+            .map(|line| line.width() as u16)
+            .max()
+            .unwrap_or(0)
+            .max(36)
+            + 6;
+        let dialog_height = (lines.len() as u16) + 4;
+        let area = Self::centered_rect(size, dialog_width, dialog_height);
+
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_bookmark_name(&self, f: &mut Frame, size: Rect, name: &str) {
+        let lines = vec![Line::from(vec![
+            Span::raw(name.to_string()),
+            Span::styled(
+                " ",
+                Style::default()
+                    .fg(self.theme.editor_cursor_char_fg)
+                    .bg(self.theme.editor_cursor_char_bg),
+            ),
+        ])];
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Bookmark name (Enter to save) ")
+            .padding(Padding::new(2, 2, 1, 1))
+            .style(
+                Style::default()
+                    .fg(self.theme.file_tree_current_file_fg)
+                    .bg(self.theme.editor_cursor_line_bg),
+            );
+
+        let area = Self::centered_rect(size, 44, 5);
+
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
     fn render_about(&self, f: &mut Frame, size: Rect) {
         let version = env!("CARGO_PKG_VERSION");
         let lines = vec![
@@ -721,3 +1537,8 @@ impl<'a> UI<'a> {
         }
     }
 }
+
+// Message shown in the bookmark list when a change couldn't be saved
+fn bookmark_error(error: anyhow::Error) -> String {
+    format!("Couldn't save bookmarks: {}", error.root_cause())
+}
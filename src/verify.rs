@@ -0,0 +1,174 @@
+use std::fmt;
+
+use crate::animation::{AnimationEngine, AnimationStep, HunkOrder};
+use crate::git::{CommitMetadata, FileChange, FileStatus};
+
+/// A file whose replayed editor buffer differs from its committed content
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub commit: String,
+    pub path: String,
+    /// First differing line (1-indexed)
+    pub line: usize,
+    /// Line in the committed content, including its terminator
+    pub expected: Option<String>,
+    /// Line in the replayed buffer, including its terminator
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |line: &Option<String>| match line {
+            Some(line) => format!("{:?}", line),
+            None => "end of file".to_string(),
+        };
+        write!(
+            f,
+            "{} {}: line {}: expected {}, got {}",
+            &self.commit[..self.commit.len().min(7)],
+            self.path,
+            self.line,
+            show(&self.expected),
+            show(&self.actual)
+        )
+    }
+}
+
+/// Replay a commit headlessly and check that every animated file ends up
+/// exactly as its `new_content`.
+///
+/// Files are compared with the last content they had in the editor, at the
+/// end of the commit, so files that are edited in several visits are only
+/// checked once complete. This includes files of replayed submodule commits.
+/// Excluded, deleted, renamed, binary and submodule entries are not typed
+/// out, so they are not checked.
+pub fn verify_commit(metadata: &CommitMetadata, order: HunkOrder) -> Vec<Mismatch> {
+    let mut engine = AnimationEngine::new(0);
+    // A zero-height viewport skips scroll calculations
+    engine.set_viewport_height(0);
+    engine.set_hunk_order(order);
+    engine.load_commit(metadata);
+
+    // Animated file and the commit it belongs to, which differs from
+    // `metadata` while a submodule commit is replayed
+    let mut current: Option<(String, FileChange)> = None;
+    // Files left so far, with the content they were left with
+    let mut edited: Vec<(String, FileChange, String)> = Vec::new();
+
+    let mut leave = |current: &mut Option<(String, FileChange)>, engine: &AnimationEngine| {
+        if let Some((commit, change)) = current.take() {
+            let content = engine.buffer.content();
+            edited.retain(|(c, other, _)| !(*c == commit && other.path == change.path));
+            edited.push((commit, change, content));
+        }
+    };
+
+    while let Some(step) = engine.peek_step() {
+        match *step {
+            AnimationStep::SwitchFile { file_index, .. } => {
+                leave(&mut current, &engine);
+                current = engine.current_metadata().and_then(|commit| {
+                    let change = commit.changes.get(file_index)?;
+                    is_animated(change).then(|| (commit.hash.clone(), change.clone()))
+                });
+            }
+            AnimationStep::ShowSubmoduleCommit { .. } => leave(&mut current, &engine),
+            _ => {}
+        }
+        engine.advance();
+    }
+    leave(&mut current, &engine);
+
+    edited
+        .iter()
+        .filter_map(|(commit, change, content)| compare(commit, change, content))
+        .collect()
+}
+
+fn is_animated(change: &FileChange) -> bool {
+    !change.is_excluded
+        && change.binary.is_none()
+        && change.submodule.is_none()
+        && !matches!(change.status, FileStatus::Deleted | FileStatus::Renamed)
+}
+
+fn compare(commit: &str, change: &FileChange, actual: &str) -> Option<Mismatch> {
+    let expected = change.new_content.as_deref().unwrap_or_default();
+    if actual == expected {
+        return None;
+    }
+
+    let mut expected_lines = expected.split_inclusive('\n');
+    let mut actual_lines = actual.split_inclusive('\n');
+    let mut line = 1;
+    loop {
+        let (expected, actual) = (expected_lines.next(), actual_lines.next());
+        if expected != actual {
+            return Some(Mismatch {
+                commit: commit.to_string(),
+                path: change.path.clone(),
+                line,
+                expected: expected.map(String::from),
+                actual: actual.map(String::from),
+            });
+        }
+        line += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{DiffHunk, LineChangeType};
+    use crate::test_support::{commit_metadata, line};
+
+    #[test]
+    fn test_diverging_replay_is_reported() {
+        use LineChangeType::*;
+        let change = FileChange {
+            path: "src/lib.rs".to_string(),
+            old_path: None,
+            status: FileStatus::Modified,
+            is_binary: false,
+            is_excluded: false,
+            exclusion_reason: None,
+            old_content: Some("a\nb\nc\n".to_string()),
+            // The hunk replaces "b" with "B"
+            new_content: Some("a\nX\nc\n".to_string()),
+            hunks: vec![DiffHunk {
+                old_start: 1,
+                old_lines: 3,
+                new_start: 1,
+                new_lines: 3,
+                lines: vec![
+                    line(Context, "a"),
+                    line(Deletion, "b"),
+                    line(Addition, "B"),
+                    line(Context, "c"),
+                ],
+            }],
+            diff: String::new(),
+            dependency_changes: Vec::new(),
+            syntax_path: None,
+            binary: None,
+            submodule: None,
+        };
+        let metadata = commit_metadata("Change b", vec![change]);
+
+        let mismatches = verify_commit(&metadata, HunkOrder::TopDown);
+        assert_eq!(
+            mismatches,
+            vec![Mismatch {
+                commit: metadata.hash.clone(),
+                path: "src/lib.rs".to_string(),
+                line: 2,
+                expected: Some("X\n".to_string()),
+                actual: Some("B\n".to_string()),
+            }]
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            r#"0123456 src/lib.rs: line 2: expected "X\n", got "B\n""#
+        );
+    }
+}